@@ -0,0 +1,170 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+const AUCTION_LOCK_REASON: &str = "auction";
+
+// Timed auction of Epic or Ssr hero, the hero is locked until it is settled
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Auction {
+    pub seller_id: AccountId,
+    pub reserve_price: U128,
+    // Block timestamp in nanoseconds
+    pub ends_at: U64,
+    pub bidder_id: Option<AccountId>,
+    pub bid: U128,
+}
+
+// Auction with its hero
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuctionView {
+    pub auction: Auction,
+    pub hero: HeroView,
+}
+
+impl Auction {
+    // Make `bid` of `bidder_id` at `now` the highest bid, return the previous bidder
+    // with the bid to refund
+    pub fn place_bid(&mut self, bidder_id: AccountId, bid: Balance, now: u64) -> Option<(AccountId, Balance)> {
+        assert!(now < self.ends_at.0, "auction has ended");
+        assert_ne!(bidder_id, self.seller_id, "seller can't bid");
+        assert!(bid >= self.reserve_price.0, "bid is below reserve price");
+        assert!(bid > self.bid.0, "bid must be higher than {}", self.bid.0);
+        let refund = self.bidder_id.replace(bidder_id).map(|previous_bidder_id| (previous_bidder_id, self.bid.0));
+        self.bid = U128(bid);
+        refund
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Put the caller's Epic or Ssr hero on auction until `ends_at`
+    #[payable]
+    pub fn create_auction(&mut self, token_id: TokenId, reserve_price: U128, ends_at: U64) {
+        assert_one_yocto();
+        let seller_id = self.assert_token_owner(&token_id);
+        let rarity = self.rarity.get(&token_id).expect("hero not found");
+        assert!(matches!(rarity, Rarity::Epic | Rarity::Ssr), "only Epic and Ssr heroes can be auctioned");
+        assert!(ends_at.0 > env::block_timestamp(), "auction must end in the future");
+        // Fail early if the hero can't change hands
+        self.internal_before_transfer(&token_id);
+
+        self.sales.remove(&token_id);
+        self.internal_lock_hero(&token_id, env::current_account_id(), AUCTION_LOCK_REASON.to_string(), U64(u64::MAX));
+        self.auctions.insert(&token_id, &Auction { seller_id, reserve_price, ends_at, bidder_id: None, bid: U128(0) });
+    }
+
+    // Cancel auction of the caller's hero which has no bids yet
+    #[payable]
+    pub fn cancel_auction(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+        let auction = self.auctions.get(&token_id).expect("auction not found");
+        assert_eq!(env::predecessor_account_id(), auction.seller_id, "only seller can cancel the auction");
+        assert!(auction.bidder_id.is_none(), "auction already has bids");
+        self.auctions.remove(&token_id);
+        self.locks.remove(&token_id);
+    }
+
+    // Bid attached deposit, the previous bidder is refunded
+    #[payable]
+    pub fn place_bid(&mut self, token_id: TokenId) {
+        let mut auction = self.auctions.get(&token_id).expect("auction not found");
+        let refund = auction.place_bid(env::predecessor_account_id(), env::attached_deposit(), env::block_timestamp());
+        if let Some((previous_bidder_id, previous_bid)) = refund {
+            Promise::new(previous_bidder_id).transfer(previous_bid);
+        }
+        self.auctions.insert(&token_id, &auction);
+    }
+
+    // Give the hero to the highest bidder and pay the seller, or return the hero
+    // to the seller if there were no bids. Anyone can call it after the auction ends
+    pub fn settle_auction(&mut self, token_id: TokenId) {
+        let auction = self.auctions.get(&token_id).expect("auction not found");
+        assert!(env::block_timestamp() >= auction.ends_at.0, "auction has not ended yet");
+        self.auctions.remove(&token_id);
+        self.locks.remove(&token_id);
+
+        // The auction lock kept the hero from changing hands, so the transfer policy
+        // is not checked again and the winning bid can't get stuck
+        if let Some(bidder_id) = auction.bidder_id {
+            self.internal_move_escrowed_hero(&token_id, &auction.seller_id, &bidder_id);
+            self.internal_pay_seller(&auction.seller_id, auction.bid.0);
+        }
+    }
+
+    // Get auction of the hero
+    pub fn get_auction(&self, token_id: TokenId) -> Option<AuctionView> {
+        let auction = self.auctions.get(&token_id)?;
+        Some(AuctionView { auction, hero: self.get_hero(token_id)? })
+    }
+
+    // Get auctions which are not settled yet
+    pub fn get_auctions(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<AuctionView> {
+        let limit = limit.unwrap_or(u64::MAX);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        self.auctions
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .filter_map(|(token_id, auction)| Some(AuctionView { auction, hero: self.get_hero(token_id)? }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    fn auction() -> Auction {
+        Auction { seller_id: account("seller.near"), reserve_price: U128(100), ends_at: U64(1_000), bidder_id: None, bid: U128(0) }
+    }
+
+    #[test]
+    fn first_bid_has_nothing_to_refund() {
+        let mut auction = auction();
+        assert_eq!(auction.place_bid(account("alice.near"), 100, 0), None);
+        assert_eq!(auction.bidder_id, Some(account("alice.near")));
+        assert_eq!(auction.bid.0, 100);
+    }
+
+    #[test]
+    fn higher_bid_refunds_previous_bidder() {
+        let mut auction = auction();
+        auction.place_bid(account("alice.near"), 150, 0);
+        assert_eq!(auction.place_bid(account("bob.near"), 200, 999), Some((account("alice.near"), 150)));
+        assert_eq!(auction.place_bid(account("alice.near"), 201, 999), Some((account("bob.near"), 200)));
+        assert_eq!(auction.bidder_id, Some(account("alice.near")));
+        assert_eq!(auction.bid.0, 201);
+    }
+
+    #[test]
+    #[should_panic(expected = "bid must be higher than 150")]
+    fn equal_bid_is_rejected() {
+        let mut auction = auction();
+        auction.place_bid(account("alice.near"), 150, 0);
+        auction.place_bid(account("bob.near"), 150, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "bid is below reserve price")]
+    fn bid_below_reserve_is_rejected() {
+        auction().place_bid(account("alice.near"), 99, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "auction has ended")]
+    fn bid_after_end_is_rejected() {
+        auction().place_bid(account("alice.near"), 100, 1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "seller can't bid")]
+    fn seller_bid_is_rejected() {
+        auction().place_bid(account("seller.near"), 100, 0);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+use crate::heroes::{hero_data, HeroData};
+
+// Time-limited crafting event with boosted drop rates of featured heroes
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Banner {
+    pub name: String,
+    // Block timestamps in nanoseconds
+    pub starts_at: U64,
+    pub ends_at: U64,
+    // Media keys of featured heroes
+    pub featured: Vec<String>,
+    // Drop weight multiplier of featured heroes
+    pub rate_up: u32,
+    // Price of a single craft on this banner
+    pub price: U128,
+}
+
+// Banner with its id
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BannerView {
+    pub banner_id: u64,
+    pub banner: Banner,
+}
+
+impl Banner {
+    pub fn is_active(&self) -> bool {
+        let now = env::block_timestamp();
+        self.starts_at.0 <= now && now < self.ends_at.0
+    }
+
+    // Drop weight of `hero` on this banner
+    pub fn weight(&self, hero: &HeroData) -> u32 {
+        if self.featured.iter().any(|media_url| media_url == hero.media_url) {
+            hero.weight * self.rate_up
+        } else {
+            hero.weight
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Add new banner and return its id
+    pub fn add_banner(&mut self, banner: Banner) -> u64 {
+        self.assert_owner();
+        assert!(banner.starts_at.0 < banner.ends_at.0, "banner must start before it ends");
+        assert!(banner.rate_up > 0, "rate up must be positive");
+        assert!(banner.rate_up <= MAX_RATE_UP, "rate up can't exceed {}", MAX_RATE_UP);
+        for media_url in banner.featured.iter() {
+            assert!(hero_data(media_url).is_some(), "unknown hero {}", media_url);
+        }
+
+        let banner_id = self.next_banner_id;
+        self.next_banner_id += 1;
+        self.banners.insert(&banner_id, &banner);
+        banner_id
+    }
+
+    // Remove banner with `banner_id`
+    pub fn remove_banner(&mut self, banner_id: u64) {
+        self.assert_owner();
+        assert!(self.banners.remove(&banner_id).is_some(), "banner not found");
+    }
+
+    // Get banner with `banner_id`
+    pub fn get_banner(&self, banner_id: u64) -> Option<Banner> {
+        self.banners.get(&banner_id)
+    }
+
+    // Get banners running at the current block
+    pub fn get_active_banners(&self) -> Vec<BannerView> {
+        self.banners
+            .iter()
+            .filter(|(_, banner)| banner.is_active())
+            .map(|(banner_id, banner)| BannerView { banner_id, banner })
+            .collect()
+    }
+}
+
+impl Contract {
+    // Get running banner with `banner_id` and the price of a craft on it
+    pub(crate) fn internal_craft_banner(&self, banner_id: Option<u64>) -> (Option<Banner>, Balance) {
+        match banner_id {
+            Some(banner_id) => {
+                let banner = self.banners.get(&banner_id).expect("banner not found");
+                assert!(banner.is_active(), "banner is not active");
+                let price = banner.price.0;
+                (Some(banner), price)
+            },
+            None => (None, CRAFT_PRICE),
+        }
+    }
+}
@@ -0,0 +1,211 @@
+// Deterministic battle engine. The outcome depends only on the fighters and the
+// seed, so clients can replay the turn log of a recorded battle
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_contract_standards::non_fungible_token::TokenId;
+
+use crate::constants::MAX_BATTLE_ROUNDS;
+use crate::heroes::HeroClass;
+
+// Hero stats at the start of the battle
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Fighter {
+    pub token_id: TokenId,
+    pub class: HeroClass,
+    pub power: u64,
+    pub health: u64,
+}
+
+// Action of a fighter, `target` indexes the enemy team or own team for heals
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Turn {
+    pub side: u8,
+    pub actor: u8,
+    pub target: u8,
+    pub heal: bool,
+    pub critical: bool,
+    pub amount: u64,
+    pub target_health: u64,
+}
+
+pub struct BattleOutcome {
+    // Winning side, `None` for a draw
+    pub winner: Option<u8>,
+    pub turns: Vec<Turn>,
+}
+
+// Xorshift generator, easy to reproduce on clients
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+// Damage in percents of power and critical hit chance of each class
+fn class_modifiers(class: HeroClass) -> (u64, u64) {
+    match class {
+        HeroClass::Tank => (75, 5),
+        HeroClass::Healer => (50, 5),
+        HeroClass::MeleeDD => (150, 10),
+        HeroClass::RangeDD => (125, 10),
+        HeroClass::Specialist => (100, 25),
+    }
+}
+
+fn alive(health: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    health.iter().enumerate().filter(|(_, health)| **health > 0).map(|(index, _)| index)
+}
+
+// Fight `teams` until one of them falls or rounds run out. Fighters act in team
+// order alternating sides, tanks draw melee attacks, range fighters finish off the
+// weakest enemy, specialists strike at random and healers heal the most wounded ally
+pub fn resolve_battle(teams: [&[Fighter]; 2], seed: u64) -> BattleOutcome {
+    let mut rng = Rng::new(seed);
+    let mut health: [Vec<u64>; 2] = [
+        teams[0].iter().map(|fighter| fighter.health).collect(),
+        teams[1].iter().map(|fighter| fighter.health).collect(),
+    ];
+    let mut turns = vec![];
+    let team_size = teams[0].len().max(teams[1].len());
+
+    for _ in 0..MAX_BATTLE_ROUNDS {
+        for index in 0..team_size {
+            for side in 0..2 {
+                if health[side].get(index).is_none_or(|health| *health == 0) {
+                    continue;
+                }
+                let enemy = 1 - side;
+                if alive(&health[enemy]).next().is_none() {
+                    return BattleOutcome { winner: Some(side as u8), turns };
+                }
+                let fighter = &teams[side][index];
+                let (damage_percent, critical_chance) = class_modifiers(fighter.class);
+                let critical = rng.below(100) < critical_chance;
+                let spread = 90 + rng.below(21);
+
+                // Healers heal the most wounded ally if anyone is wounded
+                let wounded = alive(&health[side])
+                    .map(|ally| (ally, teams[side][ally].health - health[side][ally]))
+                    .filter(|(_, missing)| *missing > 0)
+                    .max_by_key(|(_, missing)| *missing);
+                if let (HeroClass::Healer, Some((ally, missing))) = (fighter.class, wounded) {
+                    let amount = (fighter.power * 2 * spread / 100 * if critical { 2 } else { 1 }).min(missing);
+                    health[side][ally] += amount;
+                    turns.push(Turn {
+                        side: side as u8,
+                        actor: index as u8,
+                        target: ally as u8,
+                        heal: true,
+                        critical,
+                        amount,
+                        target_health: health[side][ally],
+                    });
+                    continue;
+                }
+
+                let enemies: Vec<usize> = alive(&health[enemy]).collect();
+                let target = match fighter.class {
+                    HeroClass::RangeDD => *enemies.iter().min_by_key(|enemy_index| health[enemy][**enemy_index]).unwrap(),
+                    HeroClass::Specialist => enemies[rng.below(enemies.len() as u64) as usize],
+                    _ => *enemies
+                        .iter()
+                        .find(|enemy_index| teams[enemy][**enemy_index].class == HeroClass::Tank)
+                        .unwrap_or(&enemies[0]),
+                };
+                let amount = fighter.power * damage_percent / 100 * spread / 100 * if critical { 2 } else { 1 };
+                let amount = amount.max(1).min(health[enemy][target]);
+                health[enemy][target] -= amount;
+                turns.push(Turn {
+                    side: side as u8,
+                    actor: index as u8,
+                    target: target as u8,
+                    heal: false,
+                    critical,
+                    amount,
+                    target_health: health[enemy][target],
+                });
+            }
+        }
+        for side in 0..2 {
+            if alive(&health[1 - side]).next().is_none() {
+                return BattleOutcome { winner: Some(side as u8), turns };
+            }
+        }
+    }
+
+    // Out of rounds, the team with bigger share of remaining health wins
+    let share = |side: usize| -> (u64, u64) {
+        (health[side].iter().sum(), teams[side].iter().map(|fighter| fighter.health).sum())
+    };
+    let ((left_0, total_0), (left_1, total_1)) = (share(0), share(1));
+    let winner = match (left_0 as u128 * total_1 as u128).cmp(&(left_1 as u128 * total_0 as u128)) {
+        std::cmp::Ordering::Greater => Some(0),
+        std::cmp::Ordering::Less => Some(1),
+        std::cmp::Ordering::Equal => None,
+    };
+    BattleOutcome { winner, turns }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fighter(index: u8, class: HeroClass, power: u64, health: u64) -> Fighter {
+        Fighter { token_id: index.to_string(), class, power, health }
+    }
+
+    fn teams() -> [Vec<Fighter>; 2] {
+        [
+            vec![
+                fighter(0, HeroClass::Tank, 20, 400),
+                fighter(1, HeroClass::Healer, 15, 200),
+                fighter(2, HeroClass::MeleeDD, 40, 250),
+            ],
+            vec![
+                fighter(3, HeroClass::RangeDD, 35, 220),
+                fighter(4, HeroClass::Specialist, 30, 240),
+                fighter(5, HeroClass::MeleeDD, 38, 260),
+            ],
+        ]
+    }
+
+    #[test]
+    fn same_seed_replays_same_battle() {
+        let [first, second] = teams();
+        for seed in [0, 1, 42, u64::MAX] {
+            let outcome = resolve_battle([&first, &second], seed);
+            let replay = resolve_battle([&first, &second], seed);
+            assert!(!outcome.turns.is_empty());
+            assert_eq!(outcome.winner, replay.winner);
+            assert_eq!(outcome.turns, replay.turns);
+        }
+    }
+
+    #[test]
+    fn seed_changes_battle() {
+        let [first, second] = teams();
+        let outcomes: Vec<Vec<Turn>> = (1..10).map(|seed| resolve_battle([&first, &second], seed).turns).collect();
+        assert!(outcomes.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn stronger_team_wins() {
+        let weak = vec![fighter(0, HeroClass::MeleeDD, 1, 10)];
+        let strong = vec![fighter(1, HeroClass::MeleeDD, 100, 1_000)];
+        for seed in 0..10 {
+            assert_eq!(resolve_battle([&weak, &strong], seed).winner, Some(1));
+            assert_eq!(resolve_battle([&strong, &weak], seed).winner, Some(0));
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::*;
+use near_sdk::json_types::U64;
+
+use crate::heroes::hero_score;
+
+const EXPEDITION_LOCK_REASON: &str = "expedition";
+
+// PvE expedition heroes are sent on, rewards are scaled by team strength
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Expedition {
+    pub name: String,
+    // Length in nanoseconds
+    pub duration: U64,
+    // Team score giving base rewards, weaker teams get down to half and stronger up to double
+    pub difficulty: u64,
+    // Base experience of each hero and materials of the player
+    pub experience: u64,
+    pub materials: u64,
+    // Base chance to find a hero in basis points
+    pub hero_chance: u32,
+}
+
+// Expedition with its id
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExpeditionView {
+    pub expedition_id: u64,
+    pub expedition: Expedition,
+}
+
+// Heroes sent on expedition, they are locked until rewards are claimed
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExpeditionRun {
+    pub player_id: AccountId,
+    pub expedition_id: u64,
+    // Expedition as it was when heroes were sent
+    pub expedition: Expedition,
+    pub heroes: Vec<TokenId>,
+    pub team_score: u64,
+    // Block timestamp in nanoseconds
+    pub ends_at: U64,
+}
+
+// Expedition run with its id
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExpeditionRunView {
+    pub run_id: u64,
+    pub run: ExpeditionRun,
+}
+
+// Rewards of a finished expedition
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExpeditionReward {
+    // Gained by each hero
+    pub experience: u64,
+    pub materials: u64,
+    pub hero: Option<CraftedHero>,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Add new expedition and return its id
+    pub fn add_expedition(&mut self, expedition: Expedition) -> u64 {
+        self.assert_owner();
+        assert_expedition_valid(&expedition);
+        let expedition_id = self.next_expedition_id;
+        self.next_expedition_id += 1;
+        self.expeditions.insert(&expedition_id, &expedition);
+        expedition_id
+    }
+
+    // Replace expedition with `expedition_id`, running expeditions keep old settings
+    pub fn update_expedition(&mut self, expedition_id: u64, expedition: Expedition) {
+        self.assert_owner();
+        assert_expedition_valid(&expedition);
+        assert!(self.expeditions.get(&expedition_id).is_some(), "expedition not found");
+        self.expeditions.insert(&expedition_id, &expedition);
+    }
+
+    // Remove expedition with `expedition_id`, running expeditions can still be claimed
+    pub fn remove_expedition(&mut self, expedition_id: u64) {
+        self.assert_owner();
+        assert!(self.expeditions.remove(&expedition_id).is_some(), "expedition not found");
+    }
+
+    // Get expedition with `expedition_id`
+    pub fn get_expedition(&self, expedition_id: u64) -> Option<Expedition> {
+        self.expeditions.get(&expedition_id)
+    }
+
+    // Get all expeditions heroes can be sent on
+    pub fn get_expeditions(&self) -> Vec<ExpeditionView> {
+        self.expeditions
+            .iter()
+            .map(|(expedition_id, expedition)| ExpeditionView { expedition_id, expedition })
+            .collect()
+    }
+
+    // Send team of up to 5 heroes owned by the caller on expedition, heroes are
+    // locked until rewards are claimed. Return run id
+    pub fn send_expedition(&mut self, expedition_id: u64, heroes: Vec<TokenId>) -> u64 {
+        let player_id = env::predecessor_account_id();
+        let expedition = self.expeditions.get(&expedition_id).expect("expedition not found");
+        for token_id in heroes.iter() {
+            self.assert_token_owner(token_id);
+            self.assert_not_rented(token_id);
+        }
+        let team_score = self.internal_fighters(&player_id, &heroes)
+            .iter()
+            .map(|fighter| hero_score(fighter.power, fighter.health))
+            .sum();
+        for token_id in heroes.iter() {
+            self.internal_lock_hero(token_id, env::current_account_id(), EXPEDITION_LOCK_REASON.to_string(), U64(u64::MAX));
+        }
+
+        let ends_at = U64(env::block_timestamp() + expedition.duration.0);
+        let run_id = self.next_expedition_run_id;
+        self.next_expedition_run_id += 1;
+        index_account_id(&mut self.expedition_runs_by_account, &player_id, run_id, |account_id_hash| {
+            StorageKey::ExpeditionRunsByAccountInner { account_id_hash }
+        });
+        self.expedition_runs.insert(&run_id, &ExpeditionRun { player_id, expedition_id, expedition, heroes, team_score, ends_at });
+        run_id
+    }
+
+    // Unlock heroes of the caller's finished expedition and give its rewards. Stronger
+    // teams gain more experience and materials and find heroes more often
+    pub fn claim_expedition(&mut self, run_id: u64) -> ExpeditionReward {
+        let run = self.expedition_runs.remove(&run_id).expect("expedition run not found");
+        assert_eq!(env::predecessor_account_id(), run.player_id, "only player can claim the expedition");
+        assert!(env::block_timestamp() >= run.ends_at.0, "expedition ends at {}", run.ends_at.0);
+        unindex_account_id(&mut self.expedition_runs_by_account, &run.player_id, run_id);
+
+        let rand = random_bytes(0);
+        let strength = (run.team_score * 100 / run.expedition.difficulty).clamp(50, 200);
+        let spread = 80 + rand[0] as u64 % 41;
+        let experience = run.expedition.experience * strength / 100 * spread / 100;
+        let materials = run.expedition.materials * strength / 100 * spread / 100;
+        for token_id in run.heroes.iter() {
+            if let Some(lock) = self.locks.get(token_id) {
+                if lock.locked_by == env::current_account_id() && lock.reason == EXPEDITION_LOCK_REASON {
+                    self.locks.remove(token_id);
+                }
+            }
+            self.internal_add_experience(token_id, experience);
+        }
+        self.materials.insert(&run.player_id, &(self.get_materials(run.player_id.clone()) + materials));
+
+        let hero_chance = (run.expedition.hero_chance as u64 * strength / 100).min(10_000);
+        let roll = u16::from_be_bytes([rand[1], rand[2]]) as u64 % 10_000;
+        let hero = if roll < hero_chance {
+            Some(self.internal_roll_free_hero(&run.player_id, 1))
+        } else {
+            None
+        };
+        ExpeditionReward { experience, materials, hero }
+    }
+
+    // Get expeditions of `account_id` which are not claimed yet
+    pub fn get_expedition_runs_for_account(&self, account_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<ExpeditionRunView> {
+        account_ids_page(&self.expedition_runs_by_account, &account_id, from_index, limit)
+            .into_iter()
+            .filter_map(|run_id| Some(ExpeditionRunView { run_id, run: self.expedition_runs.get(&run_id)? }))
+            .collect()
+    }
+
+    // Get materials found by `account_id` on expeditions
+    pub fn get_materials(&self, account_id: AccountId) -> u64 {
+        self.materials.get(&account_id).unwrap_or(0)
+    }
+}
+
+fn assert_expedition_valid(expedition: &Expedition) {
+    assert!(expedition.duration.0 > 0, "duration must be positive");
+    assert!(expedition.difficulty > 0, "difficulty must be positive");
+    assert!(expedition.hero_chance <= 10_000, "hero chance can't exceed 10000");
+}
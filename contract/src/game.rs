@@ -0,0 +1,197 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+use crate::heroes::hero_score;
+
+// Limits of stakes and house edge in basis points
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameConfig {
+    pub house_edge: u32,
+    pub min_stake: U128,
+    pub max_stake: U128,
+}
+
+// Result of a single game
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameRound {
+    pub player_id: AccountId,
+    pub token_id: TokenId,
+    pub stake: U128,
+    // Chance to win and rolled number in basis points, the player wins when `roll < win_chance`
+    pub win_chance: u32,
+    pub roll: u32,
+    // Paid to the player including the stake, zero if the player lost
+    pub payout: U128,
+    pub timestamp: U64,
+}
+
+// Chance of `power` and `health` hero to beat the house monster in basis points
+fn win_chance(power: u64, health: u64) -> u32 {
+    let score = hero_score(power, health);
+    let chance = score * 10_000 / (score + HOUSE_MONSTER_SCORE);
+    chance.clamp(MIN_WIN_CHANCE as u64, MAX_WIN_CHANCE as u64) as u32
+}
+
+// Paid to the winner of `stake` including the stake, rounded down so the expected
+// return never exceeds `1 - house_edge`
+fn win_payout(stake: Balance, house_edge: u32, win_chance: u32) -> Balance {
+    stake * (10_000 - house_edge) as u128 / win_chance as u128
+}
+
+// Roll in basis points from the random seed. Reducing a 64-bit number keeps the
+// modulo bias below 10^-15, unlike 16 bits where lower rolls come up more often
+fn roll(rand: &[u8]) -> u32 {
+    (u64::from_le_bytes(rand[..8].try_into().unwrap()) % 10_000) as u32
+}
+
+#[near_bindgen]
+impl Contract {
+    // Stake attached deposit on the hero used by the caller fighting the house monster.
+    // Stronger heroes win more often for a smaller payout, so the house edge is the same
+    #[payable]
+    pub fn play(&mut self, token_id: TokenId) -> GameRound {
+        let player_id = self.assert_hero_user(&token_id);
+        let stake = env::attached_deposit();
+        let config = self.get_game_config();
+        assert!(stake >= config.min_stake.0, "stake must be at least {}", config.min_stake.0);
+        assert!(stake <= config.max_stake.0, "stake must be at most {}", config.max_stake.0);
+
+        let hero = self.get_hero(token_id.clone()).expect("token is not a hero");
+        let win_chance = win_chance(hero.power, hero.health);
+        let win_payout = win_payout(stake, config.house_edge, win_chance);
+        assert!(win_payout - stake <= self.bankroll, "house can't cover the stake");
+
+        let roll = roll(&env::random_seed());
+        let payout = if roll < win_chance {
+            self.bankroll -= win_payout - stake;
+            Promise::new(player_id.clone()).transfer(win_payout);
+            win_payout
+        } else {
+            self.bankroll += stake;
+            0
+        };
+
+        let round = GameRound {
+            player_id,
+            token_id,
+            stake: U128(stake),
+            win_chance,
+            roll,
+            payout: U128(payout),
+            timestamp: U64(env::block_timestamp()),
+        };
+        log!("EVENT_JSON:{}", json!({
+            "standard": EVENT_STANDARD,
+            "version": EVENT_VERSION,
+            "event": "game_round",
+            "data": [&round],
+        }));
+        self.rounds.push(&round);
+        round
+    }
+
+    // Get played games, oldest first
+    pub fn get_rounds(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<GameRound> {
+        let limit = limit.unwrap_or(u64::MAX);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        self.rounds
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    // Get balance available to pay winnings
+    pub fn get_bankroll(&self) -> U128 {
+        U128(self.bankroll)
+    }
+
+    // Add attached deposit to the bankroll
+    #[payable]
+    pub fn fund_bankroll(&mut self) {
+        self.assert_owner();
+        self.bankroll += env::attached_deposit();
+    }
+
+    // Withdraw `amount` from the bankroll to the contract owner
+    pub fn withdraw_bankroll(&mut self, amount: U128) {
+        self.assert_owner();
+        assert!(amount.0 <= self.bankroll, "bankroll is {}", self.bankroll);
+        self.bankroll -= amount.0;
+        Promise::new(self.owner_id.clone()).transfer(amount.0);
+    }
+
+    // Get limits of stakes and house edge
+    pub fn get_game_config(&self) -> GameConfig {
+        self.game_config.get().unwrap()
+    }
+
+    // Set limits of stakes and house edge
+    pub fn set_game_config(&mut self, game_config: GameConfig) {
+        self.assert_owner();
+        // Winning must pay more than the stake even for the strongest hero
+        assert!(game_config.house_edge < 10_000 - MAX_WIN_CHANCE, "house edge must be less than {}", 10_000 - MAX_WIN_CHANCE);
+        assert!(game_config.min_stake.0 > 0, "minimum stake must be positive");
+        assert!(game_config.min_stake.0 <= game_config.max_stake.0, "minimum stake can't exceed maximum stake");
+        self.game_config.set(&game_config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn win_chance_is_clamped() {
+        assert_eq!(win_chance(0, 0), MIN_WIN_CHANCE);
+        assert_eq!(win_chance(u32::MAX as u64, 0), MAX_WIN_CHANCE);
+        assert_eq!(win_chance(HOUSE_MONSTER_SCORE, 0), 5_000);
+    }
+
+    #[test]
+    fn win_chance_grows_with_score() {
+        let chances: Vec<u32> = (0..2_000).step_by(50).map(|power| win_chance(power, 500)).collect();
+        assert!(chances.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn expected_return_keeps_house_edge() {
+        let stake = 10u128.pow(24);
+        for chance in MIN_WIN_CHANCE..=MAX_WIN_CHANCE {
+            let payout = win_payout(stake, HOUSE_EDGE, chance);
+            assert!(payout > stake);
+            // chance / 10^4 * payout / stake <= 1 - house_edge / 10^4
+            assert!(payout * chance as u128 <= stake * (10_000 - HOUSE_EDGE) as u128);
+        }
+    }
+
+    // Well mixed stand-in for the block random seed
+    fn splitmix64(nonce: u64) -> u64 {
+        let mut z = nonce.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    #[test]
+    fn roll_is_uniform() {
+        const ROLLS: u32 = 100_000;
+        let mut buckets = [0u32; 10];
+        let mut wins = 0u32;
+        for nonce in 0..ROLLS {
+            let roll = roll(&splitmix64(nonce as u64).to_le_bytes());
+            assert!(roll < 10_000);
+            buckets[roll as usize / 1_000] += 1;
+            if roll < 5_000 {
+                wins += 1;
+            }
+        }
+        // Binomial standard deviation is about 95 per bucket and 158 for wins
+        for count in buckets {
+            assert!(count.abs_diff(ROLLS / 10) < 600, "bucket has {} rolls", count);
+        }
+        assert!(wins.abs_diff(ROLLS / 2) < 1_000, "won {} rolls", wins);
+    }
+}
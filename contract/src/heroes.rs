@@ -0,0 +1,420 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::constants::{EXPERIENCE_PER_LEVEL, LEVEL_BONUS_PERCENT, STAR_BONUS_PERCENT};
+
+// Token rarity
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Rarity {
+    Common,
+    Rare,
+    Epic,
+    Ssr
+}
+
+// Hero faction
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Faction {
+    Undead,
+    Demon,
+    Human,
+    Neutral,
+    Beast,
+}
+
+// Hero role in a team
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum HeroClass {
+    Tank,
+    Healer,
+    MeleeDD,
+    RangeDD,
+    Specialist,
+}
+
+// Hero template, `media_url` key identifies it
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HeroData {
+    pub name: &'static str,
+    pub media_url: &'static str,
+    pub power: u64,
+    pub health: u64,
+    pub rarity: Rarity,
+    pub faction: Faction,
+    pub class: HeroClass,
+    // Drop weight among heroes of the same rarity
+    pub weight: u32,
+}
+
+// All heroes which can be crafted
+pub const HEROES: [HeroData; 23] = [
+    HeroData {
+        name: "Krong",
+        media_url: "UndeadArcherDD",
+        power: 20,
+        health: 50,
+        rarity: Rarity::Common,
+        faction: Faction::Undead,
+        class: HeroClass::RangeDD,
+        weight: 42,
+    },
+    HeroData {
+        name: "Freya",
+        media_url: "DemonRangeDD",
+        power: 75,
+        health: 440,
+        rarity: Rarity::Common,
+        faction: Faction::Demon,
+        class: HeroClass::RangeDD,
+        weight: 42,
+    },
+    HeroData {
+        name: "Morg",
+        media_url: "DemonTank",
+        power: 50,
+        health: 1000,
+        rarity: Rarity::Common,
+        faction: Faction::Demon,
+        class: HeroClass::Tank,
+        weight: 42,
+    },
+    HeroData {
+        name: "Slay",
+        media_url: "HumanMeleeDD",
+        power: 10,
+        health: 400,
+        rarity: Rarity::Common,
+        faction: Faction::Human,
+        class: HeroClass::MeleeDD,
+        weight: 43,
+    },
+    HeroData {
+        name: "Warg",
+        media_url: "Warg",
+        power: 54,
+        health: 1200,
+        rarity: Rarity::Common,
+        faction: Faction::Beast,
+        class: HeroClass::MeleeDD,
+        weight: 43,
+    },
+    HeroData {
+        name: "White Wolf",
+        media_url: "WhiteWolf",
+        power: 50,
+        health: 1400,
+        rarity: Rarity::Common,
+        faction: Faction::Beast,
+        class: HeroClass::MeleeDD,
+        weight: 44,
+    },
+    HeroData {
+        name: "Helga",
+        media_url: "UndeadHeal",
+        power: 20,
+        health: 50,
+        rarity: Rarity::Rare,
+        faction: Faction::Undead,
+        class: HeroClass::Healer,
+        weight: 32,
+    },
+    HeroData {
+        name: "Chi-chi",
+        media_url: "UndeadSpecialist",
+        power: 10,
+        health: 50,
+        rarity: Rarity::Rare,
+        faction: Faction::Undead,
+        class: HeroClass::Specialist,
+        weight: 32,
+    },
+    HeroData {
+        name: "Drakara",
+        media_url: "DemonHeal",
+        power: 100,
+        health: 500,
+        rarity: Rarity::Rare,
+        faction: Faction::Demon,
+        class: HeroClass::Healer,
+        weight: 32,
+    },
+    HeroData {
+        name: "Hardy",
+        media_url: "DemonMeleeDD",
+        power: 90,
+        health: 630,
+        rarity: Rarity::Rare,
+        faction: Faction::Demon,
+        class: HeroClass::MeleeDD,
+        weight: 32,
+    },
+    HeroData {
+        name: "Bronks",
+        media_url: "HumanTank",
+        power: 10,
+        health: 400,
+        rarity: Rarity::Rare,
+        faction: Faction::Human,
+        class: HeroClass::Tank,
+        weight: 32,
+    },
+    HeroData {
+        name: "Timina",
+        media_url: "HumanSpecialist",
+        power: 10,
+        health: 400,
+        rarity: Rarity::Rare,
+        faction: Faction::Human,
+        class: HeroClass::Specialist,
+        weight: 32,
+    },
+    HeroData {
+        name: "Gray wolf",
+        media_url: "NeutralWolf1",
+        power: 75,
+        health: 670,
+        rarity: Rarity::Rare,
+        faction: Faction::Neutral,
+        class: HeroClass::MeleeDD,
+        weight: 32,
+    },
+    HeroData {
+        name: "Porcupine",
+        media_url: "Porcupine",
+        power: 45,
+        health: 485,
+        rarity: Rarity::Rare,
+        faction: Faction::Beast,
+        class: HeroClass::RangeDD,
+        weight: 32,
+    },
+    HeroData {
+        name: "Unknown",
+        media_url: "UndeadMeleeDD",
+        power: 20,
+        health: 5000,
+        rarity: Rarity::Epic,
+        faction: Faction::Undead,
+        class: HeroClass::MeleeDD,
+        weight: 85,
+    },
+    HeroData {
+        name: "Berenika",
+        media_url: "HumanHeal",
+        power: 10,
+        health: 400,
+        rarity: Rarity::Epic,
+        faction: Faction::Human,
+        class: HeroClass::Healer,
+        weight: 85,
+    },
+    HeroData {
+        name: "Black Bear",
+        media_url: "BlackBear",
+        power: 55,
+        health: 1050,
+        rarity: Rarity::Epic,
+        faction: Faction::Beast,
+        class: HeroClass::Tank,
+        weight: 86,
+    },
+    HeroData {
+        name: "Dead King",
+        media_url: "UndeadTank",
+        power: 20,
+        health: 5000,
+        rarity: Rarity::Ssr,
+        faction: Faction::Undead,
+        class: HeroClass::Tank,
+        weight: 42,
+    },
+    HeroData {
+        name: "Luciy",
+        media_url: "DemonSpecialist",
+        power: 117,
+        health: 500,
+        rarity: Rarity::Ssr,
+        faction: Faction::Demon,
+        class: HeroClass::Specialist,
+        weight: 42,
+    },
+    HeroData {
+        name: "Iona",
+        media_url: "HumanRangeDD",
+        power: 10,
+        health: 400,
+        rarity: Rarity::Ssr,
+        faction: Faction::Human,
+        class: HeroClass::RangeDD,
+        weight: 42,
+    },
+    HeroData {
+        name: "Troll",
+        media_url: "NeutralTroll",
+        power: 50,
+        health: 1600,
+        rarity: Rarity::Ssr,
+        faction: Faction::Neutral,
+        class: HeroClass::Tank,
+        weight: 43,
+    },
+    HeroData {
+        name: "Wolf Whelp",
+        media_url: "WolfWhelp",
+        power: 50,
+        health: 300,
+        rarity: Rarity::Ssr,
+        faction: Faction::Beast,
+        class: HeroClass::MeleeDD,
+        weight: 43,
+    },
+    HeroData {
+        name: "Red Dragon",
+        media_url: "DragonBoss",
+        power: 20,
+        health: 5000,
+        rarity: Rarity::Ssr,
+        faction: Faction::Beast,
+        class: HeroClass::Tank,
+        weight: 44,
+    },
+];
+
+// Choose rarity using random byte
+pub fn roll_rarity(rand: u8) -> Rarity {
+    match rand {
+        0..=214 => Rarity::Common,
+        215..=240 => Rarity::Rare,
+        241..=253 => Rarity::Epic,
+        254..=255 => Rarity::Ssr,
+    }
+}
+
+// Odds of each rarity out of 65536 when crafting, same as `roll_rarity`
+const RARITY_WEIGHTS: [u32; 4] = [215 * 256, 26 * 256, 13 * 256, 2 * 256];
+
+// Odds of each rarity out of 65536 with odds of rarities above Common raised by
+// `bonus_percent`, keeping their ratios so rarer stays rarer
+pub fn boosted_rarity_weights(bonus_percent: u32) -> [u32; 4] {
+    let mut weights = RARITY_WEIGHTS.map(|weight| weight * (100 + bonus_percent) / 100);
+    weights[Rarity::Common as usize] = 65_536 - weights[1..].iter().sum::<u32>();
+    weights
+}
+
+// Choose rarity using random number with odds of `boosted_rarity_weights`
+pub fn roll_boosted_rarity(rand: u16, bonus_percent: u32) -> Rarity {
+    let weights = boosted_rarity_weights(bonus_percent);
+    let mut point = rand as u32;
+    for (rarity, weight) in [Rarity::Common, Rarity::Rare, Rarity::Epic, Rarity::Ssr].into_iter().zip(weights) {
+        if point < weight {
+            return rarity;
+        }
+        point -= weight;
+    }
+    unreachable!()
+}
+
+// Choose rarity of free heroes using random byte, weighted further towards Common
+pub fn roll_free_rarity(rand: u8) -> Rarity {
+    match rand {
+        0..=239 => Rarity::Common,
+        240..=252 => Rarity::Rare,
+        253..=254 => Rarity::Epic,
+        255 => Rarity::Ssr,
+    }
+}
+
+// Find hero template by its media key
+pub fn hero_data(media_url: &str) -> Option<&'static HeroData> {
+    HEROES.iter().find(|hero| hero.media_url == media_url)
+}
+
+// Pick hero from `candidates` with their drop weights using random number
+pub fn roll_hero<'a>(candidates: &[(&'a HeroData, u32)], rand: u16) -> Option<&'a HeroData> {
+    let total: u64 = candidates.iter().map(|(_, weight)| *weight as u64).sum();
+    let mut point = rand as u64 * total / (u16::MAX as u64 + 1);
+    for (hero, weight) in candidates {
+        if point < *weight as u64 {
+            return Some(hero);
+        }
+        point -= *weight as u64;
+    }
+    None
+}
+
+// Level reached with `experience`, limited by `maximum_level` when it is set
+pub fn hero_level(experience: u64, maximum_level: u64) -> u64 {
+    let level = 1 + experience / EXPERIENCE_PER_LEVEL;
+    if maximum_level > 0 {
+        level.min(maximum_level)
+    } else {
+        level
+    }
+}
+
+// Power and health of `hero` grown with its level and stars
+pub fn combat_stats(hero: &HeroData, stars: u64, level: u64) -> (u64, u64) {
+    let bonus = 100 + LEVEL_BONUS_PERCENT * (level - 1) + STAR_BONUS_PERCENT * stars;
+    (hero.power * bonus / 100, hero.health * bonus / 100)
+}
+
+// Overall strength of a hero with `power` and `health`
+pub fn hero_score(power: u64, health: u64) -> u64 {
+    power + health / 10
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Rolls of each rarity out of all 256 values of the random byte
+    fn rarity_counts(roll: impl Fn(u8) -> Rarity) -> [u32; 4] {
+        let mut counts = [0; 4];
+        for rand in 0..=u8::MAX {
+            counts[roll(rand) as usize] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn craft_rarity_odds() {
+        assert_eq!(rarity_counts(roll_rarity), [215, 26, 13, 2]);
+    }
+
+    #[test]
+    fn boosted_rarity_without_bonus_matches_craft_odds() {
+        assert_eq!(boosted_rarity_weights(0), RARITY_WEIGHTS);
+        for rand in 0..=u8::MAX {
+            let low = u16::from_be_bytes([rand, 0]);
+            let high = u16::from_be_bytes([rand, u8::MAX]);
+            assert_eq!(roll_boosted_rarity(low, 0), roll_rarity(rand));
+            assert_eq!(roll_boosted_rarity(high, 0), roll_rarity(rand));
+        }
+    }
+
+    #[test]
+    fn boosted_rarity_rolls_follow_weights() {
+        for bonus_percent in [0, 10, 25, 50] {
+            let mut counts = [0; 4];
+            for rand in 0..=u16::MAX {
+                counts[roll_boosted_rarity(rand, bonus_percent) as usize] += 1;
+            }
+            assert_eq!(counts, boosted_rarity_weights(bonus_percent));
+        }
+    }
+
+    #[test]
+    fn free_rarity_odds_favor_common() {
+        let craft = rarity_counts(roll_rarity);
+        let free = rarity_counts(roll_free_rarity);
+        assert_eq!(free, [240, 13, 2, 1]);
+        assert!(free[Rarity::Common as usize] > craft[Rarity::Common as usize]);
+        for rarity in [Rarity::Rare, Rarity::Epic, Rarity::Ssr] {
+            assert!(free[rarity as usize] < craft[rarity as usize]);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::*;
+use near_sdk::json_types::U64;
+
+use crate::heroes::{combat_stats, hero_level, hero_score};
+
+// Rankings maintained by the contract
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LeaderboardKind {
+    // Heroes by experience gained in the current season
+    HeroExperience,
+    // Accounts by total score of owned heroes
+    RosterPower,
+    // Accounts by PvP wins in the current season
+    PvpWins,
+}
+
+// Token or account id with its score
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeaderboardEntry {
+    pub id: String,
+    pub score: u64,
+}
+
+// Top entries ordered by score, earlier entries keep their place on ties
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    // Set score of `id`, entries with zero score are removed
+    pub fn update(&mut self, id: &str, score: u64) {
+        self.remove(id);
+        if score == 0 {
+            return;
+        }
+        let index = self.entries.partition_point(|entry| entry.score >= score);
+        if index < LEADERBOARD_SIZE as usize {
+            self.entries.insert(index, LeaderboardEntry { id: id.to_string(), score });
+            self.entries.truncate(LEADERBOARD_SIZE as usize);
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.entries.retain(|entry| entry.id != id);
+    }
+}
+
+// Current season, experience and PvP wins leaderboards are reset when a new season starts
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Season {
+    pub season: u64,
+    pub started_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Get leaderboard entries ordered by rank
+    pub fn get_leaderboard(&self, kind: LeaderboardKind, from_index: Option<u64>, limit: Option<u64>) -> Vec<LeaderboardEntry> {
+        let limit = limit.unwrap_or(u64::MAX);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        self.leaderboards
+            .get(&kind)
+            .unwrap_or_default()
+            .entries
+            .into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    // Get PvP wins of `account_id` in the current season
+    pub fn get_pvp_wins(&self, account_id: AccountId) -> u64 {
+        match self.pvp_wins.get(&account_id) {
+            Some((season, wins)) if season == self.season => wins,
+            _ => 0,
+        }
+    }
+
+    // Get experience gained by the hero in the current season
+    pub fn get_season_experience(&self, token_id: TokenId) -> u64 {
+        match self.season_experience.get(&token_id) {
+            Some((season, experience)) if season == self.season => experience,
+            _ => 0,
+        }
+    }
+
+    // Get total score of heroes owned by `account_id`
+    pub fn get_roster_power(&self, account_id: AccountId) -> u64 {
+        self.roster_power.get(&account_id).unwrap_or(0)
+    }
+
+    // Get current season
+    pub fn get_season(&self) -> Season {
+        Season { season: self.season, started_at: U64(self.season_started_at) }
+    }
+
+    // Start new season clearing experience and PvP wins leaderboards, they count
+    // from zero again as seasonal scores are tagged with the season. Roster power
+    // always reflects owned heroes so it is kept
+    pub fn start_season(&mut self) -> Season {
+        self.assert_owner();
+        self.season += 1;
+        self.season_started_at = env::block_timestamp();
+        for kind in [LeaderboardKind::HeroExperience, LeaderboardKind::PvpWins] {
+            self.leaderboards.remove(&kind);
+        }
+        self.get_season()
+    }
+}
+
+impl Contract {
+    fn internal_update_leaderboard(&mut self, kind: LeaderboardKind, update: impl FnOnce(&mut Leaderboard)) {
+        let mut leaderboard = self.leaderboards.get(&kind).unwrap_or_default();
+        update(&mut leaderboard);
+        self.leaderboards.insert(&kind, &leaderboard);
+    }
+
+    // Rank the hero by experience gained this season and move its score from the
+    // owner it was last ranked with to the current owner after the hero changed
+    pub(crate) fn internal_rank_hero(&mut self, token_id: &TokenId) {
+        let owner_id = match self.tokens.owner_by_id.get(token_id) {
+            Some(owner_id) => owner_id,
+            None => return self.internal_unrank_hero(token_id),
+        };
+        let experience = self.get_season_experience(token_id.clone());
+        self.internal_update_leaderboard(LeaderboardKind::HeroExperience, |leaderboard| {
+            leaderboard.update(token_id, experience)
+        });
+
+        let score = self.internal_hero_score(token_id);
+        if let Some((previous_owner_id, previous_score)) = self.hero_scores.insert(token_id, &(owner_id.clone(), score)) {
+            self.internal_add_roster_power(&previous_owner_id, 0, previous_score);
+        }
+        self.internal_add_roster_power(&owner_id, score, 0);
+    }
+
+    // Remove burned hero from the leaderboard and its score from the roster power
+    pub(crate) fn internal_unrank_hero(&mut self, token_id: &TokenId) {
+        self.internal_update_leaderboard(LeaderboardKind::HeroExperience, |leaderboard| leaderboard.remove(token_id));
+        self.season_experience.remove(token_id);
+        if let Some((owner_id, score)) = self.hero_scores.remove(token_id) {
+            self.internal_add_roster_power(&owner_id, 0, score);
+        }
+    }
+
+    // Count `amount` of experience gained by the hero in the current season
+    pub(crate) fn internal_gain_season_experience(&mut self, token_id: &TokenId, amount: u64) {
+        let experience = self.get_season_experience(token_id.clone()) + amount;
+        self.season_experience.insert(token_id, &(self.season, experience));
+    }
+
+    // Add `gained` and subtract `lost` score of heroes from the roster power of
+    // `account_id` and rank it
+    fn internal_add_roster_power(&mut self, account_id: &AccountId, gained: u64, lost: u64) {
+        let roster_power = self.get_roster_power(account_id.clone()) + gained - lost;
+        if roster_power == 0 {
+            self.roster_power.remove(account_id);
+        } else {
+            self.roster_power.insert(account_id, &roster_power);
+        }
+        self.internal_update_leaderboard(LeaderboardKind::RosterPower, |leaderboard| {
+            leaderboard.update(account_id.as_str(), roster_power)
+        });
+    }
+
+    // Count PvP win of `account_id` in the current season
+    pub(crate) fn internal_record_pvp_win(&mut self, account_id: &AccountId) {
+        let wins = self.get_pvp_wins(account_id.clone()) + 1;
+        self.pvp_wins.insert(account_id, &(self.season, wins));
+        self.internal_update_leaderboard(LeaderboardKind::PvpWins, |leaderboard| {
+            leaderboard.update(account_id.as_str(), wins)
+        });
+    }
+
+    fn internal_hero_score(&self, token_id: &TokenId) -> u64 {
+        let hero_data = match self.template.get(token_id).and_then(|media_url| hero_data(&media_url)) {
+            Some(hero_data) => hero_data,
+            None => return 0,
+        };
+        let stars = self.stars.get(token_id).unwrap_or(0);
+        let experience = self.experience.get(token_id).unwrap_or(0);
+        let level = hero_level(experience, self.maximum_level.get(token_id).unwrap_or(0));
+        let (power, health) = combat_stats(hero_data, stars, level);
+        hero_score(power, health)
+    }
+}
@@ -1,20 +1,75 @@
+mod auction;
+mod banners;
+mod battle;
 mod constants;
+mod expeditions;
+mod game;
+mod heroes;
+mod leaderboards;
+mod locks;
+mod market;
+mod operators;
+mod pvp;
+mod rental;
+mod search;
+mod servers;
+mod staking;
+mod stats;
+mod summon;
+mod swap;
+mod teams;
+mod tournaments;
+mod transfer;
+mod views;
 
 use near_contract_standards::non_fungible_token::{Token, TokenId, NonFungibleToken};
+use near_contract_standards::non_fungible_token::core::{NonFungibleTokenCore, NonFungibleTokenResolver};
+use near_contract_standards::non_fungible_token::events::NftBurn;
 use near_contract_standards::non_fungible_token::metadata::{
     NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata, NFT_METADATA_SPEC,
   };
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{
-    env, log, near_bindgen, PanicOnDefault, AccountId, BorshStorageKey, Promise, PromiseOrValue
+    assert_one_yocto, env, log, near_bindgen, PanicOnDefault, AccountId, Balance, BorshStorageKey, CryptoHash, Promise, PromiseOrValue
 };
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::collections::{ LazyOption, LookupMap};
-use near_sdk::json_types::Base64VecU8;
+use near_sdk::collections::{ LazyOption, LookupMap, LookupSet, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
 use near_sdk::serde_json::json;
+use std::collections::HashMap;
 
-pub use constants::{BASE_URI, DATA_IMAGE_SVG_NEAR_ICON, ONE_NEAR, ONE_YOCTO, SINGLE_CALL_GAS};
+pub use constants::{
+    BASE_URI, CRAFT_PRICE, DATA_IMAGE_SVG_NEAR_ICON, MAX_CRAFT_BATCH, MAX_RATE_UP, ONE_NEAR, ONE_YOCTO, PITY_THRESHOLD,
+    NANOSECONDS_IN_HOUR, NANOSECONDS_IN_DAY, MAX_LOCK_DURATION, STAKING_STAR_BONUS_PERCENT, DAILY_CRAFT_COOLDOWN, SUMMON_COOLDOWN,
+    SUMMON_RARITY_BONUS_PERCENT, SUMMON_FACTION_BONUS_PERCENT, MAX_SUMMON_BONUS_PERCENT, SUMMON_FACTION_RATE_UP, SINGLE_CALL_GAS, STUDIO_FEE, HOUSE_EDGE, HOUSE_MONSTER_SCORE, MIN_WIN_CHANCE,
+    MAX_WIN_CHANCE, MAX_TEAM_SIZE, MAX_TEAMS, MAX_TEAM_NAME_LENGTH, MAX_TOURNAMENT_ENTRIES, MAX_TOURNAMENT_PRIZES, LEADERBOARD_SIZE, MAX_BATTLE_ROUNDS, BATTLE_WIN_EXPERIENCE, BATTLE_LOSS_EXPERIENCE,
+    BATTLE_COOLDOWN, CHALLENGE_DEPOSIT, SWAP_DEPOSIT, EVENT_STANDARD, EVENT_VERSION,
+};
+pub use auction::{Auction, AuctionView};
+pub use banners::{Banner, BannerView};
+pub use battle::{Fighter, Turn};
+pub use expeditions::{Expedition, ExpeditionReward, ExpeditionRun, ExpeditionRunView, ExpeditionView};
+pub use game::{GameConfig, GameRound};
+pub use heroes::{Faction, HeroClass, HeroData, Rarity, HEROES};
+pub use leaderboards::{LeaderboardEntry, LeaderboardKind, Season};
+pub use locks::HeroLock;
+pub use market::{MarketFees, Sale, SaleView};
+pub use pvp::{BattleResult, BattleView, Challenge, ChallengeView};
+pub use rental::Rental;
+pub use search::HeroFilter;
+pub use staking::{Stake, StakeView};
+pub use stats::{CollectionStats, SupplyStats};
+pub use summon::SummonConfig;
+pub use swap::{SwapOffer, SwapOfferView};
+pub use teams::TeamView;
+pub use tournaments::{Tournament, TournamentEntry, TournamentView};
+pub use transfer::TransferPolicy;
+pub use views::HeroView;
+
+use heroes::{hero_data, roll_free_rarity, roll_hero, roll_rarity};
+use leaderboards::Leaderboard;
+use operators::HeroOperators;
 
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
@@ -27,25 +82,151 @@ enum StorageKey {
     TokenMetadata,
     Enumeration,
     Approval,
+    Pity,
+    Banners,
+    Template,
+    Edition,
+    Minted,
+    MaxSupply,
+    Faction,
+    Class,
+    HeroesByTemplate,
+    HeroesByTemplateInner { media_url_hash: CryptoHash },
+    Burned,
+    Operators,
+    StatsUpdatedAt,
+    Locks,
+    Soulbound,
+    StarterGranted,
+    Sales,
+    MarketFees,
+    Auctions,
+    Swaps,
+    RentalOffers,
+    Rentals,
+    GameConfig,
+    Rounds,
+    Challenges,
+    Battles,
+    Teams,
+    Expeditions,
+    ExpeditionRuns,
+    Materials,
+    GameServers,
+    Tournaments,
+    Leaderboards,
+    PvpWins,
+    Stakes,
+    Gold,
+    LastDailyCraft,
+    SummonConfig,
+    SummonCooldowns,
+    Parents,
+    Children,
+    RenterOperators,
+    SwapsByAccount,
+    SwapsByAccountInner { account_id_hash: CryptoHash },
+    ChallengesByAccount,
+    ChallengesByAccountInner { account_id_hash: CryptoHash },
+    ExpeditionRunsByAccount,
+    ExpeditionRunsByAccountInner { account_id_hash: CryptoHash },
+    BattleCooldowns,
+    SeasonExperience,
+    HeroScores,
+    RosterPower,
+}
+
+// Supply of a hero template
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HeroSupply {
+    pub minted: u64,
+    pub max_supply: Option<u64>,
 }
 
-// Token rarity
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+// Hero minted by a craft call
+#[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
-pub enum Rarity {
-    Common,
-    Rare,
-    Epic,
-    Ssr
+pub struct CraftedHero {
+    pub token_id: TokenId,
+    pub rarity: Rarity,
 }
 
-// Hero data
-pub struct HeroData {
-    name: String,
-    media_url: String,
-    power: u64,
-    health: u64,
-    rarity: Rarity, 
+// Random bytes for the `nonce`-th roll of the current block. Nonce 0 is the raw
+// block seed, others are derived from it so each roll in a batch is independent
+fn random_bytes(nonce: u8) -> Vec<u8> {
+    let seed = env::random_seed();
+    if nonce == 0 {
+        return seed;
+    }
+    env::sha256(&[seed.as_slice(), &[nonce]].concat())
+}
+
+// Generate token_id, batch rolls share the timestamp so add nonce to keep it unique
+fn hero_token_id(hero_data: &HeroData, rand: &[u8], nonce: u8) -> TokenId {
+    let timestamp: u64 = env::block_timestamp();
+    match nonce {
+        0 => format!("{}:{}:{}", &hero_data.media_url, rand[2], timestamp),
+        _ => format!("{}:{}:{}:{}", &hero_data.media_url, rand[2], timestamp, nonce),
+    }
+}
+
+// Add `id` to the ids of `account_id`, `storage_key` gives prefix of a new set
+// from the account id hash
+fn index_account_id(
+    index: &mut LookupMap<AccountId, UnorderedSet<u64>>,
+    account_id: &AccountId,
+    id: u64,
+    storage_key: impl FnOnce(CryptoHash) -> StorageKey,
+) {
+    let mut ids = index
+        .get(account_id)
+        .unwrap_or_else(|| UnorderedSet::new(storage_key(env::sha256_array(account_id.as_bytes()))));
+    ids.insert(&id);
+    index.insert(account_id, &ids);
+}
+
+// Remove `id` from the ids of `account_id`
+fn unindex_account_id(index: &mut LookupMap<AccountId, UnorderedSet<u64>>, account_id: &AccountId, id: u64) {
+    if let Some(mut ids) = index.get(account_id) {
+        ids.remove(&id);
+        if ids.is_empty() {
+            index.remove(account_id);
+        } else {
+            index.insert(account_id, &ids);
+        }
+    }
+}
+
+// Page of the ids of `account_id`
+fn account_ids_page(
+    index: &LookupMap<AccountId, UnorderedSet<u64>>,
+    account_id: &AccountId,
+    from_index: Option<u64>,
+    limit: Option<u64>,
+) -> Vec<u64> {
+    let limit = limit.unwrap_or(u64::MAX);
+    assert_ne!(limit, 0, "Cannot provide limit of 0.");
+    match index.get(account_id) {
+        Some(ids) => ids
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .collect(),
+        None => vec![],
+    }
+}
+
+// State layout of the first release, new state is appended after it
+#[derive(BorshDeserialize)]
+struct BaseState {
+    owner_id: AccountId,
+    tokens: NonFungibleToken,
+    metadata: LazyOption<NFTContractMetadata>,
+    stars: LookupMap<TokenId, u64>,
+    experience: LookupMap<TokenId, u64>,
+    maximum_level: LookupMap<TokenId, u64>,
+    rarity: LookupMap<TokenId, Rarity>,
 }
 
 #[near_bindgen]
@@ -59,6 +240,93 @@ pub struct Contract {
     experience: LookupMap<TokenId, u64>,
     maximum_level: LookupMap<TokenId, u64>,
     rarity: LookupMap<TokenId, Rarity>,
+    faction: LookupMap<TokenId, Faction>,
+    class: LookupMap<TokenId, HeroClass>,
+    // Media key of the hero template and edition number of each token
+    template: LookupMap<TokenId, String>,
+    edition: LookupMap<TokenId, u64>,
+    // Minted tokens and optional supply cap of each hero template
+    minted: LookupMap<String, u64>,
+    max_supply: LookupMap<String, u64>,
+    // Policy applied to hero stats on transfer and last stats update of each hero
+    transfer_policy: TransferPolicy,
+    stats_updated_at: LookupMap<TokenId, u64>,
+    // Heroes which can't change hands and accounts which got their starter hero
+    soulbound: LookupSet<TokenId>,
+    starter_granted: LookupSet<AccountId>,
+    // Last free daily craft of each account
+    last_daily_craft: LookupMap<AccountId, u64>,
+    // Heroes listed for a fixed price and fees taken from sales
+    sales: UnorderedMap<TokenId, Sale>,
+    market_fees: LazyOption<MarketFees>,
+    // Auctions of Epic and Ssr heroes
+    auctions: UnorderedMap<TokenId, Auction>,
+    // Offers to swap heroes between players
+    swaps: LookupMap<u64, SwapOffer>,
+    swaps_by_account: LookupMap<AccountId, UnorderedSet<u64>>,
+    next_swap_id: u64,
+    // Offered and accepted rentals of heroes
+    rental_offers: LookupMap<TokenId, Rental>,
+    rentals: LookupMap<TokenId, Rental>,
+    // Balance paying winnings of the wagering game, its settings and played games
+    bankroll: Balance,
+    game_config: LazyOption<GameConfig>,
+    rounds: Vector<GameRound>,
+    // Open PvP challenges, fought battles and time each hero can fight again
+    challenges: LookupMap<u64, Challenge>,
+    challenges_by_account: LookupMap<AccountId, UnorderedSet<u64>>,
+    next_challenge_id: u64,
+    battles: Vector<BattleResult>,
+    battle_cooldowns: LookupMap<TokenId, u64>,
+    // Named teams of heroes of each account
+    teams: LookupMap<AccountId, HashMap<String, Vec<TokenId>>>,
+    // PvE expeditions, heroes sent on them and materials found by each account
+    expeditions: UnorderedMap<u64, Expedition>,
+    next_expedition_id: u64,
+    expedition_runs: LookupMap<u64, ExpeditionRun>,
+    expedition_runs_by_account: LookupMap<AccountId, UnorderedSet<u64>>,
+    next_expedition_run_id: u64,
+    materials: LookupMap<AccountId, u64>,
+    // Accounts submitting game results
+    game_servers: UnorderedSet<AccountId>,
+    // Tournaments with paid registration
+    tournaments: UnorderedMap<u64, Tournament>,
+    next_tournament_id: u64,
+    // Rankings and PvP wins of each account tagged with the season
+    leaderboards: LookupMap<LeaderboardKind, Leaderboard>,
+    pvp_wins: LookupMap<AccountId, (u64, u64)>,
+    // Experience gained by each hero tagged with the season
+    season_experience: LookupMap<TokenId, (u64, u64)>,
+    // Owner and score each hero was last ranked with and running total of each account
+    hero_scores: LookupMap<TokenId, (AccountId, u64)>,
+    roster_power: LookupMap<AccountId, u64>,
+    season: u64,
+    season_started_at: u64,
+    // Staked heroes and in-game currency balances
+    stakes: LookupMap<TokenId, Stake>,
+    gold: LookupMap<AccountId, Balance>,
+    // Summoning settings, time each hero can summon again and lineage of summoned heroes
+    summon_config: LazyOption<SummonConfig>,
+    summon_cooldowns: LookupMap<TokenId, u64>,
+    parents: LookupMap<TokenId, (TokenId, TokenId)>,
+    children: LookupMap<TokenId, Vec<TokenId>>,
+    // Locks of heroes busy in the game
+    locks: LookupMap<TokenId, HeroLock>,
+    // Game operators allowed to update each hero by its owner and by its renter
+    operators: LookupMap<TokenId, HeroOperators>,
+    renter_operators: LookupMap<TokenId, HeroOperators>,
+    // Burned tokens of each hero template
+    burned: LookupMap<String, u64>,
+    // Deposits received for crafting
+    total_revenue: Balance,
+    // Search index of existing heroes by template
+    heroes_by_template: LookupMap<String, UnorderedSet<TokenId>>,
+    // Crafts since the last Epic or Ssr hero for each account
+    pity: LookupMap<AccountId, u64>,
+    // Crafts after which Epic or Ssr hero is guaranteed, 0 disables pity
+    pity_threshold: u64,
+    banners: UnorderedMap<u64, Banner>,
+    next_banner_id: u64,
 }
 
 #[near_bindgen]
@@ -81,8 +349,8 @@ impl Contract {
         };
         metadata.assert_valid();        
 
-        Self {
-            owner_id: owner_id.clone().into(),
+        Self::from_base_state(BaseState {
+            owner_id: owner_id.clone(),
             tokens: NonFungibleToken::new(
                 StorageKey::NonFungibleToken,
                 owner_id,
@@ -95,26 +363,131 @@ impl Contract {
             experience: LookupMap::new(StorageKey::Experience),
             maximum_level: LookupMap::new(StorageKey::MaximumLevel),
             rarity: LookupMap::new(StorageKey::Rarity),
-        }                
+        })
     }
 
-    // We don't use this method in current version
-    #[payable]
-    pub fn play(&mut self) -> u8 {
-        //let account_id = env::signer_account_id();
-        let deposit = env::attached_deposit();
-        assert!((deposit > ONE_NEAR), "not enough currency to play");
-        
-        // Toss the dice
-        let rand: u8 = *env::random_seed().get(0).unwrap();
-        return rand;
+    // Migrate state of the first release keeping its tokens and stats, state added
+    // since then starts empty with default settings
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let base: BaseState = env::state_read().expect("no state to migrate");
+        Self::from_base_state(base)
+    }
+
+    // Init template stats, edition, search index and ranking of up to `limit` heroes
+    // minted by the first release, visiting tokens after `from_token_id`. Return the
+    // last visited token to continue from, `None` once all tokens are visited
+    pub fn backfill_heroes(&mut self, from_token_id: Option<TokenId>, limit: u64) -> Option<TokenId> {
+        self.assert_owner();
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        let owner_by_id = &self.tokens.owner_by_id;
+        let token_ids: Vec<TokenId> = match from_token_id {
+            Some(from_token_id) => owner_by_id.iter_from(from_token_id).take(limit as usize).map(|(token_id, _)| token_id).collect(),
+            None => owner_by_id.iter().take(limit as usize).map(|(token_id, _)| token_id).collect(),
+        };
+        for token_id in token_ids.iter() {
+            self.internal_backfill_hero(token_id);
+        }
+        if (token_ids.len() as u64) < limit {
+            None
+        } else {
+            token_ids.last().cloned()
+        }
+    }
+
+    // Contract with `base` state and defaults of state added since the first release
+    fn from_base_state(base: BaseState) -> Self {
+        Self {
+            owner_id: base.owner_id,
+            tokens: base.tokens,
+            metadata: base.metadata,
+            stars: base.stars,
+            experience: base.experience,
+            maximum_level: base.maximum_level,
+            rarity: base.rarity,
+            faction: LookupMap::new(StorageKey::Faction),
+            class: LookupMap::new(StorageKey::Class),
+            template: LookupMap::new(StorageKey::Template),
+            edition: LookupMap::new(StorageKey::Edition),
+            minted: LookupMap::new(StorageKey::Minted),
+            max_supply: LookupMap::new(StorageKey::MaxSupply),
+            transfer_policy: TransferPolicy::KeepStats,
+            stats_updated_at: LookupMap::new(StorageKey::StatsUpdatedAt),
+            soulbound: LookupSet::new(StorageKey::Soulbound),
+            starter_granted: LookupSet::new(StorageKey::StarterGranted),
+            last_daily_craft: LookupMap::new(StorageKey::LastDailyCraft),
+            sales: UnorderedMap::new(StorageKey::Sales),
+            market_fees: LazyOption::new(
+                StorageKey::MarketFees,
+                Some(&MarketFees { studio_fee: STUDIO_FEE, royalties: HashMap::new() }),
+            ),
+            auctions: UnorderedMap::new(StorageKey::Auctions),
+            swaps: LookupMap::new(StorageKey::Swaps),
+            swaps_by_account: LookupMap::new(StorageKey::SwapsByAccount),
+            next_swap_id: 0,
+            rental_offers: LookupMap::new(StorageKey::RentalOffers),
+            rentals: LookupMap::new(StorageKey::Rentals),
+            bankroll: 0,
+            game_config: LazyOption::new(
+                StorageKey::GameConfig,
+                Some(&GameConfig { house_edge: HOUSE_EDGE, min_stake: U128(ONE_NEAR), max_stake: U128(10 * ONE_NEAR) }),
+            ),
+            rounds: Vector::new(StorageKey::Rounds),
+            challenges: LookupMap::new(StorageKey::Challenges),
+            challenges_by_account: LookupMap::new(StorageKey::ChallengesByAccount),
+            next_challenge_id: 0,
+            battles: Vector::new(StorageKey::Battles),
+            battle_cooldowns: LookupMap::new(StorageKey::BattleCooldowns),
+            teams: LookupMap::new(StorageKey::Teams),
+            expeditions: UnorderedMap::new(StorageKey::Expeditions),
+            next_expedition_id: 0,
+            expedition_runs: LookupMap::new(StorageKey::ExpeditionRuns),
+            expedition_runs_by_account: LookupMap::new(StorageKey::ExpeditionRunsByAccount),
+            next_expedition_run_id: 0,
+            materials: LookupMap::new(StorageKey::Materials),
+            game_servers: UnorderedSet::new(StorageKey::GameServers),
+            tournaments: UnorderedMap::new(StorageKey::Tournaments),
+            next_tournament_id: 0,
+            leaderboards: LookupMap::new(StorageKey::Leaderboards),
+            pvp_wins: LookupMap::new(StorageKey::PvpWins),
+            season_experience: LookupMap::new(StorageKey::SeasonExperience),
+            hero_scores: LookupMap::new(StorageKey::HeroScores),
+            roster_power: LookupMap::new(StorageKey::RosterPower),
+            season: 0,
+            season_started_at: env::block_timestamp(),
+            stakes: LookupMap::new(StorageKey::Stakes),
+            gold: LookupMap::new(StorageKey::Gold),
+            summon_config: LazyOption::new(
+                StorageKey::SummonConfig,
+                Some(&SummonConfig { fee: U128(CRAFT_PRICE), cooldown: U64(SUMMON_COOLDOWN), burn_parents: false }),
+            ),
+            summon_cooldowns: LookupMap::new(StorageKey::SummonCooldowns),
+            parents: LookupMap::new(StorageKey::Parents),
+            children: LookupMap::new(StorageKey::Children),
+            locks: LookupMap::new(StorageKey::Locks),
+            operators: LookupMap::new(StorageKey::Operators),
+            renter_operators: LookupMap::new(StorageKey::RenterOperators),
+            burned: LookupMap::new(StorageKey::Burned),
+            total_revenue: 0,
+            heroes_by_template: LookupMap::new(StorageKey::HeroesByTemplate),
+            pity: LookupMap::new(StorageKey::Pity),
+            pity_threshold: PITY_THRESHOLD,
+            banners: UnorderedMap::new(StorageKey::Banners),
+            next_banner_id: 0,
+        }
     }
 
-    // Update hero statistics
+    // Update hero statistics, only game operators approved by the hero user can call it
     pub fn update_hero_stats(&mut self, token_id: TokenId , new_stars: u64, new_experience: u64, new_maximum_level: u64) {
+        self.assert_operator(&token_id);
+        let experience = self.experience.get(&token_id).unwrap_or(0);
+        self.internal_gain_season_experience(&token_id, new_experience.saturating_sub(experience));
         self.stars.insert(&token_id, &new_stars);
         self.experience.insert(&token_id, &new_experience);
         self.maximum_level.insert(&token_id, &new_maximum_level);
+        self.stats_updated_at.insert(&token_id, &env::block_timestamp());
+        self.internal_rank_hero(&token_id);
     }
 
     // Get statistics of a hero
@@ -122,281 +495,339 @@ impl Contract {
         (self.stars.get(&token_id), self.experience.get(&token_id), self.maximum_level.get(&token_id), self.rarity.get(&token_id))
     }
 
-    // Mint nft ans send them to `username` account
-    #[payable]
-    pub fn craft_new_hero(&mut self, username: String) -> TokenId {
-        // Choose rarity
-        let rand: u8 = *env::random_seed().get(0).unwrap();
-        let rarity = match rand {
-            0..=214 => Rarity::Common,
-            215..=240 => Rarity::Rare,
-            241..=253 => Rarity::Epic,
-            254..=255 => Rarity::Ssr,
-        };
+    // Get number of crafts since the last Epic or Ssr hero of `account_id`
+    pub fn get_pity(&self, account_id: AccountId) -> u64 {
+        self.pity.get(&account_id).unwrap_or(0)
+    }
 
-        let rand: u8 = *env::random_seed().get(1).unwrap();
-
-        // Define hero
-        let hero_data = match rarity {
-            Rarity::Common => {
-                match rand {
-                    0..=41 => HeroData {
-                        name: String::from("Krong"),
-                        media_url: String::from("UndeadArcherDD"),
-                        power: 20,
-                        health: 50,
-                        rarity: rarity,
-                    },
-                    42..=83 => HeroData {
-                        name: String::from("Freya"),
-                        media_url: String::from("DemonRangeDD"),
-                        power: 75,
-                        health: 440,
-                        rarity: rarity,
-                    },
-                    84..=125 => HeroData {
-                        name: String::from("Morg"),
-                        media_url: String::from("DemonTank"),
-                        power: 50,
-                        health: 1000,
-                        rarity: rarity,
-                    },
-                    126..=168 => HeroData {
-                        name: String::from("Slay"),
-                        media_url: String::from("HumanMeleeDD"),
-                        power: 10,
-                        health: 400,
-                        rarity: rarity,
-                    },
-                    169..=211 => HeroData {
-                        name: String::from("Warg"),
-                        media_url: String::from("Warg"),
-                        power: 54,
-                        health: 1200,
-                        rarity: rarity,
-                    },
-                    _ => HeroData {
-                        name: String::from("White Wolf"),
-                        media_url: String::from("WhiteWolf"),
-                        power: 50,
-                        health: 1400,
-                        rarity: rarity,
-                    },
-                }
-            },
-            Rarity::Rare => {
-                match rand {
-                    0..=31 => HeroData {
-                        name: String::from("Helga"),
-                        media_url: String::from("UndeadHeal"),
-                        power: 20,
-                        health: 50,
-                        rarity: rarity,
-                    },
-                    32..=63 => HeroData {
-                        name: String::from("Chi-chi"),
-                        media_url: String::from("UndeadSpecialist"),
-                        power: 10,
-                        health: 50,
-                        rarity: rarity,
-                    },
-                    64..=95 => HeroData {
-                        name: String::from("Drakara"),
-                        media_url: String::from("DemonHeal"),
-                        power: 100,
-                        health: 500,
-                        rarity: rarity,
-                    },
-                    96..=127 => HeroData {
-                        name: String::from("Hardy"),
-                        media_url: String::from("DemonMeleeDD"),
-                        power: 90,
-                        health: 630,
-                        rarity: rarity,
-                    },
-                    128..=159 => HeroData {
-                        name: String::from("Bronks"),
-                        media_url: String::from("HumanTank"),
-                        power: 10,
-                        health: 400,
-                        rarity: rarity,
-                    },
-                    160..=191 => HeroData {
-                        name: String::from("Timina"),
-                        media_url: String::from("HumanSpecialist"),
-                        power: 10,
-                        health: 400,
-                        rarity: rarity,
-                    },
-                    192..=223=> HeroData {
-                        name: String::from("Gray wolf"),
-                        media_url: String::from("NeutralWolf1"),
-                        power: 75,
-                        health: 670,
-                        rarity: rarity,
-                    },
-                    _ => HeroData {
-                        name: String::from("Porcupine"),
-                        media_url: String::from("Porcupine"),
-                        power: 45,
-                        health: 485,
-                        rarity: rarity,
-                    },
-                }
+    // Get number of crafts guaranteeing Epic or Ssr hero
+    pub fn get_pity_threshold(&self) -> u64 {
+        self.pity_threshold
+    }
+
+    // Set number of crafts guaranteeing Epic or Ssr hero, 0 disables pity
+    pub fn set_pity_threshold(&mut self, pity_threshold: u64) {
+        self.assert_owner();
+        self.pity_threshold = pity_threshold;
+    }
+
+    // Get faction and class of a hero
+    pub fn get_attributes(&self, token_id: TokenId) -> Option<(Faction, HeroClass)> {
+        Some((self.faction.get(&token_id)?, self.class.get(&token_id)?))
+    }
+
+    // Get all hero templates which can be crafted
+    pub fn get_hero_templates(&self) -> Vec<&'static HeroData> {
+        HEROES.iter().collect()
+    }
+
+    // Get hero template and edition number of a hero
+    pub fn get_edition(&self, token_id: TokenId) -> Option<(String, u64)> {
+        Some((self.template.get(&token_id)?, self.edition.get(&token_id)?))
+    }
+
+    // Get minted tokens and supply cap of `media_url` hero template
+    pub fn get_hero_supply(&self, media_url: String) -> HeroSupply {
+        assert!(hero_data(&media_url).is_some(), "unknown hero {}", media_url);
+        HeroSupply {
+            minted: self.minted.get(&media_url).unwrap_or(0),
+            max_supply: self.max_supply.get(&media_url),
+        }
+    }
+
+    // Limit number of `media_url` heroes, `None` removes the limit
+    pub fn set_max_supply(&mut self, media_url: String, max_supply: Option<u64>) {
+        self.assert_owner();
+        assert!(hero_data(&media_url).is_some(), "unknown hero {}", media_url);
+        match max_supply {
+            Some(max_supply) => {
+                let minted = self.minted.get(&media_url).unwrap_or(0);
+                assert!(max_supply >= minted, "{} heroes are already minted", minted);
+                self.max_supply.insert(&media_url, &max_supply);
             },
-            Rarity::Epic => {
-                match rand {
-                    0..=84 => HeroData {
-                        name: String::from("Unknown"),
-                        media_url: String::from("UndeadMeleeDD"),
-                        power: 20,
-                        health: 5000,
-                        rarity: rarity,
-                    },
-                    85..=169 => HeroData {
-                        name: String::from("Berenika"),
-                        media_url: String::from("HumanHeal"),
-                        power: 10,
-                        health: 400,
-                        rarity: rarity,
-                    },
-                    _ => HeroData {
-                        name: String::from("Black Bear"),
-                        media_url: String::from("BlackBear"),
-                        power: 55,
-                        health: 1050,
-                        rarity: rarity,
-                    },
-                }
+            None => {
+                self.max_supply.remove(&media_url);
             },
-            Rarity::Ssr => {
-                match rand {
-                    0..=41 => HeroData {
-                        name: String::from("Dead King"),
-                        media_url: String::from("UndeadTank"),
-                        power: 20,
-                        health: 5000,
-                        rarity: rarity,
-                    },
-                    42..=83 => HeroData {
-                        name: String::from("Luciy"),
-                        media_url: String::from("DemonSpecialist"),
-                        power: 117,
-                        health: 500,
-                        rarity: rarity,
-                    },
-                    84..=125 => HeroData {
-                        name: String::from("Iona"),
-                        media_url: String::from("HumanRangeDD"),
-                        power: 10,
-                        health: 400,
-                        rarity: rarity,
-                    },
-                    126..=168 => HeroData {
-                        name: String::from("Troll"),
-                        media_url: String::from("NeutralTroll"),
-                        power: 50,
-                        health: 1600,
-                        rarity: rarity,
-                    },
-                    169..=211 => HeroData {
-                        name: String::from("Wolf Whelp"),
-                        media_url: String::from("WolfWhelp"),
-                        power: 50,
-                        health: 300,
-                        rarity: rarity,
-                    },
-                    _ => HeroData {
-                        name: String::from("Red Dragon"),
-                        media_url: String::from("DragonBoss"),
-                        power: 20,
-                        health: 5000,
-                        rarity: rarity,
-                    },
-                }
+        }
+    }
+
+    // Mint nft and send it to `username` account, drops are resolved against
+    // `banner_id` banner if given
+    #[payable]
+    pub fn craft_new_hero(&mut self, username: String, banner_id: Option<u64>) -> TokenId {
+        let (banner, price) = self.internal_craft_banner(banner_id);
+        assert!(env::attached_deposit() >= price, "not enough currency to craft a hero");
+        let receiver_id = AccountId::try_from(username).unwrap();
+        self.total_revenue += env::attached_deposit();
+        self.internal_craft_hero(&receiver_id, 0, banner.as_ref()).token_id
+    }
+
+    // Mint `count` heroes in one transaction and send them to `receiver_id` account
+    #[payable]
+    pub fn craft_heroes(&mut self, receiver_id: AccountId, count: u8, banner_id: Option<u64>) -> Vec<CraftedHero> {
+        assert!(count > 0, "count must be positive");
+        assert!(count <= MAX_CRAFT_BATCH, "can't craft more than {} heroes at once", MAX_CRAFT_BATCH);
+        let (banner, price) = self.internal_craft_banner(banner_id);
+        assert!(
+            env::attached_deposit() >= price * count as u128,
+            "not enough currency to craft {} heroes", count
+        );
+        self.total_revenue += env::attached_deposit();
+
+        (0..count).map(|nonce| self.internal_craft_hero(&receiver_id, nonce, banner.as_ref())).collect()
+    }
+
+    // Mint free Common hero to `account_id` which can't be transferred, once per account
+    pub fn grant_starter_hero(&mut self, account_id: AccountId) -> TokenId {
+        self.assert_owner();
+        assert!(self.starter_granted.insert(&account_id), "{} already got a starter hero", account_id);
+
+        let rand = random_bytes(0);
+        let hero_data = self.internal_roll_hero(Rarity::Common, u16::from_be_bytes([rand[1], rand[4]]), |hero| hero.weight);
+        let token_id = self.internal_mint_hero(hero_token_id(hero_data, &rand, 0), &account_id, hero_data).token_id;
+        self.soulbound.insert(&token_id);
+        token_id
+    }
+
+    // Craft a free hero for the caller once per cooldown. It is rolled with Common
+    // weighted odds which don't count towards pity and can't be transferred
+    pub fn claim_daily_hero(&mut self) -> CraftedHero {
+        let account_id = env::predecessor_account_id();
+        let remaining = self.get_daily_hero_cooldown(account_id.clone()).0;
+        assert_eq!(remaining, 0, "next free hero in {} nanoseconds", remaining);
+        self.last_daily_craft.insert(&account_id, &env::block_timestamp());
+        let hero = self.internal_roll_free_hero(&account_id, 0);
+        self.soulbound.insert(&hero.token_id);
+        hero
+    }
+
+    // Get nanoseconds until `account_id` can claim a free hero, 0 if it can claim now
+    pub fn get_daily_hero_cooldown(&self, account_id: AccountId) -> U64 {
+        let available_at = self.last_daily_craft
+            .get(&account_id)
+            .map_or(0, |claimed_at| claimed_at + DAILY_CRAFT_COOLDOWN);
+        U64(available_at.saturating_sub(env::block_timestamp()))
+    }
+
+    // Check if the hero can't change hands
+    pub fn is_soulbound(&self, token_id: TokenId) -> bool {
+        self.soulbound.contains(&token_id)
+    }
+
+    // Check if `account_id` already got a starter hero
+    pub fn has_starter_hero(&self, account_id: AccountId) -> bool {
+        self.starter_granted.contains(&account_id)
+    }
+
+    // Burn hero owned by the caller
+    #[payable]
+    pub fn burn_hero(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+        let owner_id = self.assert_token_owner(&token_id);
+        self.assert_not_locked(&token_id);
+        self.assert_not_rented(&token_id);
+        self.internal_burn_hero(&owner_id, &token_id);
+    }
+}
+
+impl Contract {
+    fn assert_owner(&self) {
+        assert_eq!(env::predecessor_account_id(), self.owner_id, "only contract owner can call this method");
+    }
+
+    // Apply pity of `account_id` to rolled rarity and update the counter
+    fn internal_apply_pity(&mut self, account_id: &AccountId, rarity: Rarity, rand: u8) -> Rarity {
+        let pity = self.get_pity(account_id.clone()) + 1;
+        let rarity = match rarity {
+            Rarity::Common | Rarity::Rare if self.pity_threshold > 0 && pity >= self.pity_threshold => {
+                // Keep Epic to Ssr odds of the regular roll
+                log!("pity reached for {}", account_id);
+                if rand % 15 < 2 { Rarity::Ssr } else { Rarity::Epic }
             },
+            rarity => rarity,
         };
 
-        // Generate token_id
-        let timestamp: u64 = env::block_timestamp();
-        let rand: u8 = *env::random_seed().get(2).unwrap();
-        let token_id: String = format!("{}:{}:{}", &hero_data.media_url, rand, timestamp);
+        match rarity {
+            Rarity::Common | Rarity::Rare => self.pity.insert(account_id, &pity),
+            Rarity::Epic | Rarity::Ssr => self.pity.remove(account_id),
+        };
+        rarity
+    }
+
+    fn is_sold_out(&self, hero: &HeroData) -> bool {
+        match self.max_supply.get(&hero.media_url.to_string()) {
+            Some(max_supply) => self.minted.get(&hero.media_url.to_string()).unwrap_or(0) >= max_supply,
+            None => false,
+        }
+    }
+
+    // Pick available hero of `rarity` with drop weights given by `weight`. Falls back to
+    // other rarities from the most common one when all heroes of `rarity` are sold out
+    fn internal_roll_hero(&self, rarity: Rarity, rand: u16, weight: impl Fn(&HeroData) -> u32) -> &'static HeroData {
+        [rarity, Rarity::Common, Rarity::Rare, Rarity::Epic, Rarity::Ssr]
+            .iter()
+            .find_map(|rarity| {
+                let candidates: Vec<(&HeroData, u32)> = HEROES
+                    .iter()
+                    .filter(|hero| hero.rarity == *rarity && !self.is_sold_out(hero))
+                    .map(|hero| (hero, weight(hero)))
+                    .collect();
+                roll_hero(&candidates, rand)
+            })
+            .expect("all heroes are sold out")
+    }
+
+    // Roll a hero, mint it to `receiver_id` and init its stats
+    fn internal_craft_hero(&mut self, receiver_id: &AccountId, nonce: u8, banner: Option<&Banner>) -> CraftedHero {
+        let rand = random_bytes(nonce);
+        let rarity = roll_rarity(rand[0]);
+        let rarity = self.internal_apply_pity(receiver_id, rarity, rand[3]);
+
+        // Choose hero of rolled rarity, banner boosts featured heroes
+        let hero_data = self.internal_roll_hero(rarity, u16::from_be_bytes([rand[1], rand[4]]), |hero| {
+            banner.map_or(hero.weight, |banner| banner.weight(hero))
+        });
+
+        self.internal_mint_hero(hero_token_id(hero_data, &rand, nonce), receiver_id, hero_data)
+    }
+
+    // Init hero minted by the first release from the template in its token id prefix,
+    // editions continue from heroes of the same template. Tokens which already have a
+    // template or aren't heroes are skipped
+    fn internal_backfill_hero(&mut self, token_id: &TokenId) {
+        if self.template.contains_key(token_id) {
+            return;
+        }
+        if let Some(hero_data) = token_id.split(':').next().and_then(hero_data) {
+            let template = hero_data.media_url.to_string();
+            let edition = self.minted.get(&template).unwrap_or(0) + 1;
+            self.minted.insert(&template, &edition);
+            self.internal_init_hero(token_id, hero_data, edition);
+        }
+    }
+
+    // Init template stats and edition of the hero, index and rank it
+    fn internal_init_hero(&mut self, token_id: &TokenId, hero_data: &HeroData, edition: u64) {
+        let template = hero_data.media_url.to_string();
+        self.rarity.insert(token_id, &hero_data.rarity);
+        self.faction.insert(token_id, &hero_data.faction);
+        self.class.insert(token_id, &hero_data.class);
+        self.template.insert(token_id, &template);
+        self.edition.insert(token_id, &edition);
+        self.internal_index_hero(token_id, &template);
+        self.internal_rank_hero(token_id);
+    }
+
+    // Roll a free hero with Common weighted odds without touching pity and mint it
+    // to `receiver_id`
+    fn internal_roll_free_hero(&mut self, receiver_id: &AccountId, nonce: u8) -> CraftedHero {
+        let rand = random_bytes(nonce);
+        let rarity = roll_free_rarity(rand[0]);
+        let hero_data = self.internal_roll_hero(rarity, u16::from_be_bytes([rand[1], rand[4]]), |hero| hero.weight);
+        self.internal_mint_hero(hero_token_id(hero_data, &rand, nonce), receiver_id, hero_data)
+    }
+
+    // Remove hero of `owner_id` with its stats
+    fn internal_burn_hero(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+        self.tokens.owner_by_id.remove(token_id);
+        if let Some(token_metadata_by_id) = &mut self.tokens.token_metadata_by_id {
+            token_metadata_by_id.remove(token_id);
+        }
+        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
+            let mut token_ids = tokens_per_owner.get(owner_id).unwrap();
+            token_ids.remove(token_id);
+            if token_ids.is_empty() {
+                tokens_per_owner.remove(owner_id);
+            } else {
+                tokens_per_owner.insert(owner_id, &token_ids);
+            }
+        }
+        if let Some(approvals_by_id) = &mut self.tokens.approvals_by_id {
+            approvals_by_id.remove(token_id);
+        }
+        if let Some(next_approval_id_by_id) = &mut self.tokens.next_approval_id_by_id {
+            next_approval_id_by_id.remove(token_id);
+        }
+
+        self.stars.remove(token_id);
+        self.experience.remove(token_id);
+        self.maximum_level.remove(token_id);
+        self.rarity.remove(token_id);
+        self.faction.remove(token_id);
+        self.class.remove(token_id);
+        self.edition.remove(token_id);
+        self.operators.remove(token_id);
+        self.renter_operators.remove(token_id);
+        self.stats_updated_at.remove(token_id);
+        self.locks.remove(token_id);
+        self.soulbound.remove(token_id);
+        self.sales.remove(token_id);
+        self.rental_offers.remove(token_id);
+        self.rentals.remove(token_id);
+        self.summon_cooldowns.remove(token_id);
+        self.battle_cooldowns.remove(token_id);
+        self.internal_remove_from_teams(owner_id, token_id);
+        if let Some(template) = self.template.remove(token_id) {
+            self.burned.insert(&template, &(self.burned.get(&template).unwrap_or(0) + 1));
+            self.internal_unindex_hero(token_id, &template);
+        }
+        self.internal_unrank_hero(token_id);
+
+        NftBurn { owner_id, token_ids: &[token_id], authorized_id: None, memo: None }.emit();
+    }
+
+    // Mint hero of `hero_data` template to `receiver_id` and init its stats
+    fn internal_mint_hero(&mut self, token_id: TokenId, receiver_id: &AccountId, hero_data: &HeroData) -> CraftedHero {
         log!("token id: {}", token_id.clone());
+        let template = hero_data.media_url.to_string();
+        let edition = self.minted.get(&template).unwrap_or(0) + 1;
+        let max_supply = self.max_supply.get(&template);
+        self.minted.insert(&template, &edition);
 
-        let contract_id = env::current_account_id();
-        let root_id = AccountId::try_from(contract_id).unwrap();
         let media_url: String = format!("{}.png", &hero_data.media_url);
         let media_hash = Base64VecU8(env::sha256(media_url.as_bytes()));
         log!("media url: {}", media_url.clone());
 
-        // Default to common token
+        let edition_title = match max_supply {
+            Some(max_supply) => format!("#{} of {}", edition, max_supply),
+            None => format!("#{}", edition),
+        };
+        let timestamp: u64 = env::block_timestamp();
         let token_metadata = TokenMetadata {
-            title: Some(format!("{} {}/{}", &hero_data.name, &hero_data.power, &hero_data.health)),
+            title: Some(format!("{} {} {}/{}", &hero_data.name, edition_title, &hero_data.power, &hero_data.health)),
             description: Some(format!("{}/{}", &hero_data.power, &hero_data.health)),
             media: Some(media_url),
             media_hash: Some(media_hash),
-            copies: Some(1u64),
+            copies: max_supply,
             issued_at: Some(timestamp.to_string()),
             expires_at: None,
             starts_at: None,
             updated_at: None,
-            extra: None,
+            extra: Some(json!({
+                "rarity": hero_data.rarity,
+                "faction": hero_data.faction,
+                "class": hero_data.class,
+                "edition": edition,
+            }).to_string()),
             reference: None,
             reference_hash: None,
         };
 
-        // Mint NFT   
-        self.nft_mint(token_id.clone(), root_id.clone(), token_metadata.clone());
-
-        // Transfer NFT to new owner
-        log!("username: {}", username.clone());
-        let receiver_id = AccountId::try_from(username).unwrap();
-        log!("receiver id: {}", receiver_id.clone());
-        log!("token_id: {}", token_id.clone());
-        env::promise_create(
-            root_id,
-            "nft_transfer",
-            json!({
-                "token_id": token_id.clone(),
-                "receiver_id": receiver_id,
-            })
-            .to_string()
-            .as_bytes(),
-            ONE_YOCTO,
-            SINGLE_CALL_GAS,
-        );
-        log!("Success! NFT transfering for {}! Token ID = {}", receiver_id.clone(), token_id.clone());
+        // Mint NFT directly to the new owner
+        self.tokens.internal_mint(token_id.clone(), receiver_id.clone(), Some(token_metadata));
+        log!("Success! NFT minted for {}! Token ID = {}", receiver_id.clone(), token_id.clone());
 
         // Init token stats
         self.stars.insert(&token_id, &0);
         self.experience.insert(&token_id, &0);
         self.maximum_level.insert(&token_id, &0);
-        self.rarity.insert(&token_id, &hero_data.rarity);
+        self.internal_init_hero(&token_id, hero_data, edition);
 
-        token_id
-    }
-
-    // Mint a new token with ID=token_id belonging to receiver_id.
-    ///
-    /// Since this example implements metadata, it also requires per-token metadata to be provided
-    /// in this call. self.tokens.mint will also require it to be Some, since
-    /// StorageKey::TokenMetadata was provided at initialization.
-    ///
-    /// self.tokens.mint will enforce predecessor_account_id to equal the owner_id given in
-    /// initialization call to new.
-    #[payable]
-    pub fn nft_mint(
-        &mut self,
-        token_id: TokenId,
-        receiver_id: AccountId,
-        token_metadata: TokenMetadata,
-    ) -> Token {
-        self.tokens.internal_mint(token_id, receiver_id, Some(token_metadata))
+        CraftedHero { token_id, rarity: hero_data.rarity }
     }
 }
 
-// Implement NFT standart
-near_contract_standards::impl_non_fungible_token_core!(Contract, tokens);
+// Implement NFT standart, core methods are implemented in `transfer` module
 near_contract_standards::impl_non_fungible_token_approval!(Contract, tokens);
 near_contract_standards::impl_non_fungible_token_enumeration!(Contract, tokens);
 
@@ -0,0 +1,61 @@
+use crate::*;
+use near_sdk::json_types::U64;
+
+// Lock forbidding the hero to change hands while it is busy in the game
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HeroLock {
+    pub locked_by: AccountId,
+    pub reason: String,
+    // Block timestamp in nanoseconds
+    pub expires_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Lock the hero until `expires_at`, only approved game operators can call it
+    pub fn lock_hero(&mut self, token_id: TokenId, reason: String, expires_at: U64) {
+        self.assert_operator(&token_id);
+        assert!(expires_at.0 > env::block_timestamp(), "lock must expire in the future");
+        // Operators approved by the renter can't lock the hero beyond the rental
+        let max_expires_at = match self.get_rental(token_id.clone()) {
+            Some(rental) => rental.expires_at.0.min(env::block_timestamp() + MAX_LOCK_DURATION),
+            None => env::block_timestamp() + MAX_LOCK_DURATION,
+        };
+        assert!(expires_at.0 <= max_expires_at, "lock can't expire after {}", max_expires_at);
+        if let Some(lock) = self.get_lock(token_id.clone()) {
+            assert_eq!(lock.locked_by, env::predecessor_account_id(), "hero is locked by {}", lock.locked_by);
+        }
+        self.internal_lock_hero(&token_id, env::predecessor_account_id(), reason, expires_at);
+    }
+
+    // Unlock the hero, the account which locked it can call it. The hero user can also
+    // remove locks of game operators neither the owner nor the renter approve anymore
+    pub fn unlock_hero(&mut self, token_id: TokenId) {
+        let lock = self.get_lock(token_id.clone()).expect("hero is not locked");
+        let account_id = env::predecessor_account_id();
+        let revoked = lock.locked_by != env::current_account_id()
+            && self.get_hero_user(token_id.clone()) == Some(account_id.clone())
+            && !self.is_approved_operator(&token_id, &lock.locked_by);
+        assert!(lock.locked_by == account_id || revoked, "hero is locked by {}", lock.locked_by);
+        self.locks.remove(&token_id);
+    }
+
+    // Get lock of the hero if it has not expired yet
+    pub fn get_lock(&self, token_id: TokenId) -> Option<HeroLock> {
+        self.locks.get(&token_id).filter(|lock| lock.expires_at.0 > env::block_timestamp())
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_lock_hero(&mut self, token_id: &TokenId, locked_by: AccountId, reason: String, expires_at: U64) {
+        self.locks.insert(token_id, &HeroLock { locked_by, reason, expires_at });
+    }
+
+    // Panic if the hero is locked
+    pub(crate) fn assert_not_locked(&self, token_id: &TokenId) {
+        if let Some(lock) = self.get_lock(token_id.clone()) {
+            panic!("hero is locked by {} for {}", lock.locked_by, lock.reason);
+        }
+    }
+}
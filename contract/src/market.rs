@@ -0,0 +1,186 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use std::collections::HashMap;
+
+// Hero listed for a fixed price
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Sale {
+    pub owner_id: AccountId,
+    pub price: U128,
+}
+
+// Sale joined with the hero stats
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleView {
+    pub owner_id: AccountId,
+    pub price: U128,
+    pub hero: HeroView,
+}
+
+// Fees taken from every sale in basis points
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MarketFees {
+    // Paid to the contract owner
+    pub studio_fee: u32,
+    pub royalties: HashMap<AccountId, u32>,
+}
+
+// Split `price` into studio fee, royalties and the rest paid to the seller
+fn split_price(price: Balance, market_fees: &MarketFees) -> (Balance, Vec<(AccountId, Balance)>, Balance) {
+    let studio_fee = price * market_fees.studio_fee as u128 / 10_000;
+    let royalties: Vec<(AccountId, Balance)> = market_fees
+        .royalties
+        .iter()
+        .map(|(account_id, royalty)| (account_id.clone(), price * *royalty as u128 / 10_000))
+        .collect();
+    let payout = price - studio_fee - royalties.iter().map(|(_, amount)| amount).sum::<Balance>();
+    (studio_fee, royalties, payout)
+}
+
+#[near_bindgen]
+impl Contract {
+    // List the caller's hero for `price`
+    #[payable]
+    pub fn list_hero(&mut self, token_id: TokenId, price: U128) {
+        assert_one_yocto();
+        let owner_id = self.assert_token_owner(&token_id);
+        assert!(price.0 > 0, "price must be positive");
+        // Fail early if the hero can't change hands
+        self.internal_before_transfer(&token_id);
+        self.sales.insert(&token_id, &Sale { owner_id, price });
+    }
+
+    // Change price of the caller's listed hero
+    #[payable]
+    pub fn update_sale_price(&mut self, token_id: TokenId, price: U128) {
+        assert_one_yocto();
+        let owner_id = self.assert_token_owner(&token_id);
+        assert!(price.0 > 0, "price must be positive");
+        assert!(self.sales.get(&token_id).is_some(), "hero is not listed");
+        self.sales.insert(&token_id, &Sale { owner_id, price });
+    }
+
+    // Remove the caller's hero from sale
+    #[payable]
+    pub fn delist_hero(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+        self.assert_token_owner(&token_id);
+        assert!(self.sales.remove(&token_id).is_some(), "hero is not listed");
+    }
+
+    // Buy listed hero, deposit above the price is refunded
+    #[payable]
+    pub fn buy_hero(&mut self, token_id: TokenId) {
+        let sale = self.sales.get(&token_id).expect("hero is not listed");
+        let buyer_id = env::predecessor_account_id();
+        let deposit = env::attached_deposit();
+        assert_ne!(buyer_id, sale.owner_id, "can't buy own hero");
+        assert_eq!(self.tokens.owner_by_id.get(&token_id), Some(sale.owner_id.clone()), "sale is outdated");
+        assert!(deposit >= sale.price.0, "not enough currency to buy the hero");
+
+        self.internal_sell_hero(&token_id, &sale.owner_id, &buyer_id, sale.price.0);
+        if deposit > sale.price.0 {
+            Promise::new(buyer_id).transfer(deposit - sale.price.0);
+        }
+    }
+
+    // Get sale of the hero
+    pub fn get_sale(&self, token_id: TokenId) -> Option<SaleView> {
+        let sale = self.sales.get(&token_id)?;
+        Some(SaleView { owner_id: sale.owner_id, price: sale.price, hero: self.get_hero(token_id)? })
+    }
+
+    // Get listed heroes matching `filter`, pagination is applied to the matching sales
+    pub fn get_sales(&self, filter: Option<HeroFilter>, from_index: Option<u64>, limit: Option<u64>) -> Vec<SaleView> {
+        let limit = limit.unwrap_or(u64::MAX);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        self.sales
+            .iter()
+            .filter_map(|(token_id, sale)| {
+                Some(SaleView { owner_id: sale.owner_id, price: sale.price, hero: self.get_hero(token_id)? })
+            })
+            .filter(|sale| filter.as_ref().is_none_or(|filter| filter.matches_hero(&sale.hero)))
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    // Get fees taken from every sale
+    pub fn get_market_fees(&self) -> MarketFees {
+        self.market_fees.get().unwrap()
+    }
+
+    // Set fees taken from every sale
+    pub fn set_market_fees(&mut self, market_fees: MarketFees) {
+        self.assert_owner();
+        let total: u32 = market_fees.studio_fee + market_fees.royalties.values().sum::<u32>();
+        assert!(total < 10_000, "fees must be less than 100%");
+        self.market_fees.set(&market_fees);
+    }
+}
+
+impl Contract {
+    // Transfer hero from `seller_id` to `buyer_id` and pay `price` out to the seller minus fees
+    pub(crate) fn internal_sell_hero(&mut self, token_id: &TokenId, seller_id: &AccountId, buyer_id: &AccountId, price: Balance) {
+        self.internal_move_hero(token_id, seller_id, buyer_id);
+        self.internal_pay_seller(seller_id, price);
+    }
+
+    // Pay `price` out to the seller minus studio fee and royalties
+    pub(crate) fn internal_pay_seller(&mut self, seller_id: &AccountId, price: Balance) {
+        let (studio_fee, royalties, payout) = split_price(price, &self.get_market_fees());
+        if studio_fee > 0 {
+            Promise::new(self.owner_id.clone()).transfer(studio_fee);
+        }
+        for (account_id, amount) in royalties {
+            if amount > 0 {
+                Promise::new(account_id).transfer(amount);
+            }
+        }
+        Promise::new(seller_id.clone()).transfer(payout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(name: &str) -> AccountId {
+        name.parse().unwrap()
+    }
+
+    #[test]
+    fn split_price_takes_studio_fee_and_royalties() {
+        let market_fees = MarketFees {
+            studio_fee: STUDIO_FEE,
+            royalties: HashMap::from([(account("artist.near"), 250), (account("guild.near"), 100)]),
+        };
+        let (studio_fee, royalties, payout) = split_price(10_000, &market_fees);
+        assert_eq!(studio_fee, 500);
+        assert_eq!(HashMap::<AccountId, Balance>::from_iter(royalties), HashMap::from([
+            (account("artist.near"), 250),
+            (account("guild.near"), 100),
+        ]));
+        assert_eq!(payout, 9_150);
+    }
+
+    #[test]
+    fn split_price_pays_seller_the_rounding() {
+        let market_fees = MarketFees { studio_fee: 333, royalties: HashMap::from([(account("artist.near"), 333)]) };
+        for price in [1, 7, 299, 1_000_001, 10u128.pow(24) + 3] {
+            let (studio_fee, royalties, payout) = split_price(price, &market_fees);
+            let royalty: Balance = royalties.iter().map(|(_, amount)| amount).sum();
+            assert_eq!(studio_fee + royalty + payout, price);
+            assert_eq!(studio_fee, price * 333 / 10_000);
+        }
+    }
+
+    #[test]
+    fn split_price_without_fees_pays_seller_everything() {
+        let market_fees = MarketFees { studio_fee: 0, royalties: HashMap::new() };
+        assert_eq!(split_price(12_345, &market_fees), (0, vec![], 12_345));
+    }
+}
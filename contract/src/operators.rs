@@ -0,0 +1,114 @@
+use crate::*;
+use std::collections::HashSet;
+
+// Game operators approved by the hero user, who is its renter while the hero is
+// rented and its owner otherwise. Owner and renter approvals are kept apart and
+// bound to the user who gave them, so they stop working once the hero changes
+// hands or the rental ends
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct HeroOperators {
+    pub user_id: AccountId,
+    pub account_ids: HashSet<AccountId>,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Allow game server `operator_id` to update stats of the hero used by the caller
+    #[payable]
+    pub fn approve_operator(&mut self, token_id: TokenId, operator_id: AccountId) {
+        assert_one_yocto();
+        assert!(self.game_servers.contains(&operator_id), "{} is not a game server", operator_id);
+        let user_id = self.assert_hero_user(&token_id);
+        let rented = self.get_rental(token_id.clone()).is_some();
+        let mut operators = match self.internal_operators(&token_id, rented) {
+            Some(operators) if operators.user_id == user_id => operators,
+            _ => HeroOperators { user_id, account_ids: HashSet::new() },
+        };
+        operators.account_ids.insert(operator_id);
+        self.internal_set_operators(&token_id, rented, &operators);
+    }
+
+    // Revoke rights of `operator_id` to update stats of the hero used by the caller
+    #[payable]
+    pub fn revoke_operator(&mut self, token_id: TokenId, operator_id: AccountId) {
+        assert_one_yocto();
+        let user_id = self.assert_hero_user(&token_id);
+        let rented = self.get_rental(token_id.clone()).is_some();
+        if let Some(mut operators) = self.internal_operators(&token_id, rented) {
+            if operators.user_id == user_id && operators.account_ids.remove(&operator_id) {
+                self.internal_set_operators(&token_id, rented, &operators);
+            }
+        }
+    }
+
+    // Get game operators approved by the current user of the hero which are still game servers
+    pub fn get_operators(&self, token_id: TokenId) -> Vec<AccountId> {
+        let user_id = match self.get_hero_user(token_id.clone()) {
+            Some(user_id) => user_id,
+            None => return vec![],
+        };
+        let rented = self.get_rental(token_id.clone()).is_some();
+        match self.internal_operators(&token_id, rented) {
+            Some(operators) if operators.user_id == user_id => operators.account_ids
+                .into_iter()
+                .filter(|account_id| self.game_servers.contains(account_id))
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+impl Contract {
+    // Approvals given by the renter if `rented`, otherwise by the owner
+    fn internal_operators(&self, token_id: &TokenId, rented: bool) -> Option<HeroOperators> {
+        if rented {
+            self.renter_operators.get(token_id)
+        } else {
+            self.operators.get(token_id)
+        }
+    }
+
+    fn internal_set_operators(&mut self, token_id: &TokenId, rented: bool, operators: &HeroOperators) {
+        if rented {
+            self.renter_operators.insert(token_id, operators);
+        } else {
+            self.operators.insert(token_id, operators);
+        }
+    }
+
+    // Check if `account_id` is a game operator approved by the owner or the active renter of the hero
+    pub(crate) fn is_approved_operator(&self, token_id: &TokenId, account_id: &AccountId) -> bool {
+        let owner_id = self.tokens.owner_by_id.get(token_id);
+        let renter_id = self.get_rental(token_id.clone()).map(|rental| rental.user_id);
+        let approved = |operators: Option<HeroOperators>, user_id: Option<AccountId>| {
+            operators.is_some_and(|operators| Some(&operators.user_id) == user_id.as_ref() && operators.account_ids.contains(account_id))
+        };
+        self.game_servers.contains(account_id)
+            && (approved(self.operators.get(token_id), owner_id) || approved(self.renter_operators.get(token_id), renter_id))
+    }
+}
+
+impl Contract {
+    // Panic unless the caller owns the hero, return the owner
+    pub(crate) fn assert_token_owner(&self, token_id: &TokenId) -> AccountId {
+        let owner_id = self.tokens.owner_by_id.get(token_id).expect("Token not found");
+        assert_eq!(env::predecessor_account_id(), owner_id, "only token owner can call this method");
+        owner_id
+    }
+
+    // Panic unless the caller is the current user of the hero, return the user
+    pub(crate) fn assert_hero_user(&self, token_id: &TokenId) -> AccountId {
+        let user_id = self.get_hero_user(token_id.clone()).expect("Token not found");
+        assert_eq!(env::predecessor_account_id(), user_id, "only hero user can call this method");
+        user_id
+    }
+
+    // Panic unless the caller is a game operator approved by the current user of the hero
+    pub(crate) fn assert_operator(&self, token_id: &TokenId) {
+        let operator_id = env::predecessor_account_id();
+        assert!(
+            self.get_operators(token_id.clone()).contains(&operator_id),
+            "{} is not an approved operator of {}", operator_id, token_id
+        );
+    }
+}
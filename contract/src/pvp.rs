@@ -0,0 +1,218 @@
+use crate::*;
+use crate::battle::{resolve_battle, Fighter, Turn};
+use near_sdk::json_types::{U128, U64};
+use std::collections::HashSet;
+
+// Challenge to fight the team of the challenger until it expires
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Challenge {
+    pub challenger_id: AccountId,
+    pub opponent_id: AccountId,
+    pub team: Vec<TokenId>,
+    // Block timestamp in nanoseconds
+    pub expires_at: U64,
+    // Storage deposit refunded to the challenger
+    pub deposit: U128,
+}
+
+// Challenge with its id
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChallengeView {
+    pub challenge_id: u64,
+    pub challenge: Challenge,
+}
+
+// Fought battle, its turns are replayed from the fighters and the seed
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BattleResult {
+    pub challenger_id: AccountId,
+    pub opponent_id: AccountId,
+    // Teams with their stats at the start
+    pub challenger_team: Vec<Fighter>,
+    pub opponent_team: Vec<Fighter>,
+    pub seed: U64,
+    // `None` for a draw
+    pub winner_id: Option<AccountId>,
+    pub timestamp: U64,
+}
+
+// Battle with its id and turn log
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BattleView {
+    pub battle_id: u64,
+    pub result: BattleResult,
+    pub turns: Vec<Turn>,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Challenge `opponent_id` to fight `team` of heroes used by the caller until
+    // `expires_at`, return challenge id. Attached deposit covers storage until the
+    // challenge is removed
+    #[payable]
+    pub fn challenge(&mut self, opponent_id: AccountId, team: Vec<TokenId>, expires_at: U64) -> u64 {
+        let deposit = env::attached_deposit();
+        assert_eq!(deposit, CHALLENGE_DEPOSIT, "attach {} yoctoNEAR to challenge", CHALLENGE_DEPOSIT);
+        let challenger_id = env::predecessor_account_id();
+        assert_ne!(challenger_id, opponent_id, "can't challenge yourself");
+        assert!(expires_at.0 > env::block_timestamp(), "challenge must expire in the future");
+        self.internal_fighters(&challenger_id, &team);
+
+        let challenge_id = self.next_challenge_id;
+        self.next_challenge_id += 1;
+        for account_id in [&challenger_id, &opponent_id] {
+            index_account_id(&mut self.challenges_by_account, account_id, challenge_id, |account_id_hash| {
+                StorageKey::ChallengesByAccountInner { account_id_hash }
+            });
+        }
+        self.challenges.insert(&challenge_id, &Challenge { challenger_id, opponent_id, team, expires_at, deposit: U128(deposit) });
+        challenge_id
+    }
+
+    // Withdraw the challenge refunding its deposit to the challenger. Its challenger
+    // or opponent can call it, anyone once the challenge has expired
+    pub fn cancel_challenge(&mut self, challenge_id: u64) {
+        let challenge = self.internal_remove_challenge(challenge_id);
+        let account_id = env::predecessor_account_id();
+        assert!(
+            account_id == challenge.challenger_id
+                || account_id == challenge.opponent_id
+                || env::block_timestamp() >= challenge.expires_at.0,
+            "only challenger or opponent can cancel the challenge before it expires"
+        );
+        Promise::new(challenge.challenger_id).transfer(challenge.deposit.0);
+    }
+
+    // Fight the challenge with `team` of heroes used by the caller. Heroes of both
+    // teams gain experience, winners more than losers, and can't fight again until
+    // the cooldown passes
+    pub fn accept_challenge(&mut self, challenge_id: u64, team: Vec<TokenId>) -> BattleView {
+        let challenge = self.internal_remove_challenge(challenge_id);
+        assert_eq!(env::predecessor_account_id(), challenge.opponent_id, "only opponent can accept the challenge");
+        assert!(env::block_timestamp() < challenge.expires_at.0, "challenge has expired");
+        Promise::new(challenge.challenger_id.clone()).transfer(challenge.deposit.0);
+
+        let challenger_team = self.internal_fighters(&challenge.challenger_id, &challenge.team);
+        let opponent_team = self.internal_fighters(&challenge.opponent_id, &team);
+        for fighter in challenger_team.iter().chain(opponent_team.iter()) {
+            let remaining = self.get_battle_cooldown(fighter.token_id.clone()).0;
+            assert_eq!(remaining, 0, "{} can fight again in {} nanoseconds", fighter.token_id, remaining);
+            self.battle_cooldowns.insert(&fighter.token_id, &(env::block_timestamp() + BATTLE_COOLDOWN));
+        }
+        let rand = random_bytes(0);
+        let seed = u64::from_le_bytes(rand[..8].try_into().unwrap());
+        let outcome = resolve_battle([&challenger_team, &opponent_team], seed);
+
+        for (side, fighters) in [&challenger_team, &opponent_team].into_iter().enumerate() {
+            let experience = match outcome.winner {
+                Some(winner) if winner as usize == side => BATTLE_WIN_EXPERIENCE,
+                _ => BATTLE_LOSS_EXPERIENCE,
+            };
+            for fighter in fighters.iter() {
+                self.internal_add_experience(&fighter.token_id, experience);
+            }
+        }
+
+        let winner_id = outcome.winner.map(|winner| match winner {
+            0 => challenge.challenger_id.clone(),
+            _ => challenge.opponent_id.clone(),
+        });
+        if let Some(winner_id) = winner_id.as_ref() {
+            self.internal_record_pvp_win(winner_id);
+        }
+        let result = BattleResult {
+            challenger_id: challenge.challenger_id,
+            opponent_id: challenge.opponent_id,
+            challenger_team,
+            opponent_team,
+            seed: U64(seed),
+            winner_id,
+            timestamp: U64(env::block_timestamp()),
+        };
+        let battle_id = self.battles.len();
+        log!("EVENT_JSON:{}", json!({
+            "standard": EVENT_STANDARD,
+            "version": EVENT_VERSION,
+            "event": "battle",
+            "data": [{
+                "battle_id": battle_id,
+                "challenger_id": &result.challenger_id,
+                "opponent_id": &result.opponent_id,
+                "winner_id": &result.winner_id,
+            }],
+        }));
+        self.battles.push(&result);
+        BattleView { battle_id, result, turns: outcome.turns }
+    }
+
+    // Get challenges made or received by `account_id`
+    pub fn get_challenges_for_account(&self, account_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<ChallengeView> {
+        account_ids_page(&self.challenges_by_account, &account_id, from_index, limit)
+            .into_iter()
+            .filter_map(|challenge_id| Some(ChallengeView { challenge_id, challenge: self.challenges.get(&challenge_id)? }))
+            .collect()
+    }
+
+    // Get nanoseconds until the hero can fight again, 0 if it can fight now
+    pub fn get_battle_cooldown(&self, token_id: TokenId) -> U64 {
+        let available_at = self.battle_cooldowns.get(&token_id).unwrap_or(0);
+        U64(available_at.saturating_sub(env::block_timestamp()))
+    }
+
+    // Get battle with its turn log replayed for the client
+    pub fn get_battle(&self, battle_id: u64) -> Option<BattleView> {
+        let result = self.battles.get(battle_id)?;
+        let turns = resolve_battle([&result.challenger_team, &result.opponent_team], result.seed.0).turns;
+        Some(BattleView { battle_id, result, turns })
+    }
+
+    // Get fought battles without turn logs, oldest first
+    pub fn get_battles(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<BattleResult> {
+        let limit = limit.unwrap_or(u64::MAX);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        self.battles
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .collect()
+    }
+}
+
+impl Contract {
+    // Remove challenge from the challenges and the index of its accounts
+    fn internal_remove_challenge(&mut self, challenge_id: u64) -> Challenge {
+        let challenge = self.challenges.remove(&challenge_id).expect("challenge not found");
+        unindex_account_id(&mut self.challenges_by_account, &challenge.challenger_id, challenge_id);
+        unindex_account_id(&mut self.challenges_by_account, &challenge.opponent_id, challenge_id);
+        challenge
+    }
+
+    // Panic unless `team` is a valid team of unlocked heroes used by `account_id`,
+    // return heroes with their current stats
+    pub(crate) fn internal_fighters(&self, account_id: &AccountId, team: &[TokenId]) -> Vec<Fighter> {
+        assert!(!team.is_empty(), "team can't be empty");
+        assert!(team.len() <= MAX_TEAM_SIZE as usize, "team can't have more than {} heroes", MAX_TEAM_SIZE);
+        assert_eq!(team.iter().collect::<HashSet<_>>().len(), team.len(), "team can't have duplicate heroes");
+        team.iter()
+            .map(|token_id| {
+                let hero = self.get_hero(token_id.clone()).expect("token is not a hero");
+                assert_eq!(self.get_hero_user(token_id.clone()).as_ref(), Some(account_id), "{} is not used by {}", token_id, account_id);
+                self.assert_not_locked(token_id);
+                Fighter { token_id: token_id.clone(), class: hero.class, power: hero.power, health: hero.health }
+            })
+            .collect()
+    }
+
+    // Add `amount` of experience to the hero
+    pub(crate) fn internal_add_experience(&mut self, token_id: &TokenId, amount: u64) {
+        let experience = self.experience.get(token_id).unwrap_or(0);
+        self.experience.insert(token_id, &(experience + amount));
+        self.internal_gain_season_experience(token_id, amount);
+        self.stats_updated_at.insert(token_id, &env::block_timestamp());
+        self.internal_rank_hero(token_id);
+    }
+}
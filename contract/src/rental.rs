@@ -0,0 +1,133 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+// Usage rights of the hero given by its owner to another player
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Rental {
+    pub owner_id: AccountId,
+    pub user_id: AccountId,
+    // Fee paid to the owner when the rental is accepted
+    pub price: U128,
+    // Block timestamp in nanoseconds
+    pub expires_at: U64,
+}
+
+impl Rental {
+    // Check if the hero is still rented at `now`
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.expires_at.0
+    }
+
+    // Split `deposit` into the fee paid to the owner and the rest refunded to the user
+    pub fn split_deposit(&self, deposit: Balance) -> (Balance, Balance) {
+        assert!(deposit >= self.price.0, "not enough currency to rent the hero");
+        (self.price.0, deposit - self.price.0)
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Offer `user_id` to use the caller's hero until `expires_at` for `price`
+    #[payable]
+    pub fn offer_rental(&mut self, token_id: TokenId, user_id: AccountId, expires_at: U64, price: U128) {
+        assert_one_yocto();
+        let owner_id = self.assert_token_owner(&token_id);
+        assert_ne!(owner_id, user_id, "can't rent hero to its owner");
+        assert!(expires_at.0 > env::block_timestamp(), "rental must expire in the future");
+        assert!(self.get_rental(token_id.clone()).is_none(), "hero is already rented");
+        self.rental_offers.insert(&token_id, &Rental { owner_id, user_id, price, expires_at });
+    }
+
+    // Withdraw rental offer of the caller's hero
+    #[payable]
+    pub fn cancel_rental_offer(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+        self.assert_token_owner(&token_id);
+        assert!(self.rental_offers.remove(&token_id).is_some(), "rental offer not found");
+    }
+
+    // Accept rental offer attaching its price, the hero can't change hands until the rental expires
+    #[payable]
+    pub fn accept_rental(&mut self, token_id: TokenId) {
+        let rental = self.rental_offers.remove(&token_id).expect("rental offer not found");
+        assert_eq!(env::predecessor_account_id(), rental.user_id, "rental is offered to {}", rental.user_id);
+        assert!(rental.is_active(env::block_timestamp()), "rental offer has expired");
+        assert_eq!(self.tokens.owner_by_id.get(&token_id), Some(rental.owner_id.clone()), "rental offer is outdated");
+        // Fail early if the hero can't change hands
+        self.internal_before_transfer(&token_id);
+        let (fee, refund) = rental.split_deposit(env::attached_deposit());
+
+        if fee > 0 {
+            Promise::new(rental.owner_id.clone()).transfer(fee);
+        }
+        if refund > 0 {
+            Promise::new(rental.user_id.clone()).transfer(refund);
+        }
+        // Approvals of a previous rental don't carry over
+        self.renter_operators.remove(&token_id);
+        self.rentals.insert(&token_id, &rental);
+    }
+
+    // Get rental offer of the hero
+    pub fn get_rental_offer(&self, token_id: TokenId) -> Option<Rental> {
+        self.rental_offers.get(&token_id)
+    }
+
+    // Get rental of the hero if it has not expired yet
+    pub fn get_rental(&self, token_id: TokenId) -> Option<Rental> {
+        self.rentals.get(&token_id).filter(|rental| rental.is_active(env::block_timestamp()))
+    }
+
+    // Get account playing the hero: its renter while the hero is rented, otherwise its owner
+    pub fn get_hero_user(&self, token_id: TokenId) -> Option<AccountId> {
+        match self.get_rental(token_id.clone()) {
+            Some(rental) => Some(rental.user_id),
+            None => self.tokens.owner_by_id.get(&token_id),
+        }
+    }
+}
+
+impl Contract {
+    // Panic if the hero is rented
+    pub(crate) fn assert_not_rented(&self, token_id: &TokenId) {
+        if let Some(rental) = self.get_rental(token_id.clone()) {
+            panic!("hero is rented by {} until {}", rental.user_id, rental.expires_at.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rental(price: Balance) -> Rental {
+        Rental {
+            owner_id: "owner.near".parse().unwrap(),
+            user_id: "user.near".parse().unwrap(),
+            price: U128(price),
+            expires_at: U64(1_000),
+        }
+    }
+
+    #[test]
+    fn rental_is_active_until_it_expires() {
+        assert!(rental(0).is_active(0));
+        assert!(rental(0).is_active(999));
+        assert!(!rental(0).is_active(1_000));
+    }
+
+    #[test]
+    fn deposit_pays_fee_and_refunds_the_rest() {
+        assert_eq!(rental(100).split_deposit(100), (100, 0));
+        assert_eq!(rental(100).split_deposit(250), (100, 150));
+        assert_eq!(rental(0).split_deposit(0), (0, 0));
+        assert_eq!(rental(0).split_deposit(5), (0, 5));
+    }
+
+    #[test]
+    #[should_panic(expected = "not enough currency to rent the hero")]
+    fn deposit_below_price_is_rejected() {
+        rental(100).split_deposit(99);
+    }
+}
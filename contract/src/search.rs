@@ -0,0 +1,99 @@
+use crate::*;
+use near_sdk::collections::UnorderedSet;
+
+use crate::heroes::hero_level;
+
+// Criteria of `query_heroes`, missing fields match any hero
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HeroFilter {
+    pub media_url: Option<String>,
+    pub rarity: Option<Rarity>,
+    pub faction: Option<Faction>,
+    pub class: Option<HeroClass>,
+    pub min_level: Option<u64>,
+    pub max_level: Option<u64>,
+}
+
+impl HeroFilter {
+    fn matches_template(&self, hero: &HeroData) -> bool {
+        self.media_url.as_ref().is_none_or(|media_url| media_url == hero.media_url)
+            && self.rarity.is_none_or(|rarity| rarity == hero.rarity)
+            && self.faction.is_none_or(|faction| faction == hero.faction)
+            && self.class.is_none_or(|class| class == hero.class)
+    }
+
+    fn matches_level(&self, level: u64) -> bool {
+        self.min_level.is_none_or(|min_level| level >= min_level)
+            && self.max_level.is_none_or(|max_level| level <= max_level)
+    }
+
+    pub(crate) fn matches_hero(&self, hero: &HeroView) -> bool {
+        hero_data(&hero.media_url).is_some_and(|hero_data| self.matches_template(hero_data))
+            && self.matches_level(hero.level)
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Get heroes matching `filter` with their stats. Pagination is applied to the
+    // matching heroes, which are ordered by template
+    pub fn query_heroes(&self, filter: HeroFilter, from_index: Option<u64>, limit: Option<u64>) -> Vec<HeroView> {
+        let limit = limit.unwrap_or(u64::MAX);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        let filter_level = filter.min_level.is_some() || filter.max_level.is_some();
+
+        let mut skip = from_index.unwrap_or(0);
+        let mut heroes = vec![];
+        for hero in HEROES.iter().filter(|hero| filter.matches_template(hero)) {
+            let token_ids = match self.heroes_by_template.get(&hero.media_url.to_string()) {
+                Some(token_ids) => token_ids,
+                None => continue,
+            };
+            // Whole template can be skipped when all its heroes match
+            if !filter_level && skip >= token_ids.len() {
+                skip -= token_ids.len();
+                continue;
+            }
+
+            for token_id in token_ids.iter() {
+                if filter_level {
+                    let experience = self.experience.get(&token_id).unwrap_or(0);
+                    let maximum_level = self.maximum_level.get(&token_id).unwrap_or(0);
+                    if !filter.matches_level(hero_level(experience, maximum_level)) {
+                        continue;
+                    }
+                }
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                heroes.extend(self.get_hero(token_id));
+                if heroes.len() as u64 == limit {
+                    return heroes;
+                }
+            }
+        }
+        heroes
+    }
+}
+
+impl Contract {
+    // Add hero to the search index of its template
+    pub(crate) fn internal_index_hero(&mut self, token_id: &TokenId, media_url: &String) {
+        let mut token_ids = self.heroes_by_template.get(media_url).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::HeroesByTemplateInner {
+                media_url_hash: env::sha256_array(media_url.as_bytes()),
+            })
+        });
+        token_ids.insert(token_id);
+        self.heroes_by_template.insert(media_url, &token_ids);
+    }
+    // Remove hero from the search index of its template
+    pub(crate) fn internal_unindex_hero(&mut self, token_id: &TokenId, media_url: &String) {
+        if let Some(mut token_ids) = self.heroes_by_template.get(media_url) {
+            token_ids.remove(token_id);
+            self.heroes_by_template.insert(media_url, &token_ids);
+        }
+    }
+}
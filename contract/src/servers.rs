@@ -0,0 +1,28 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    // Allow `account_id` to act as game server
+    pub fn add_game_server(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.game_servers.insert(&account_id);
+    }
+
+    // Revoke game server rights of `account_id`
+    pub fn remove_game_server(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        assert!(self.game_servers.remove(&account_id), "{} is not a game server", account_id);
+    }
+
+    // Get accounts acting as game servers
+    pub fn get_game_servers(&self) -> Vec<AccountId> {
+        self.game_servers.to_vec()
+    }
+}
+
+impl Contract {
+    // Panic unless the caller is a game server
+    pub(crate) fn assert_game_server(&self) {
+        assert!(self.game_servers.contains(&env::predecessor_account_id()), "only game server can call this method");
+    }
+}
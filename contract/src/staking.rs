@@ -0,0 +1,149 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+const STAKING_LOCK_REASON: &str = "staking";
+
+// Hero staked by its owner, it stays locked until unstaked
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Stake {
+    pub owner_id: AccountId,
+    // Block timestamp in nanoseconds
+    pub staked_at: U64,
+    // Gold accrued per day, fixed when the hero is staked
+    pub daily_reward: U128,
+    // Gold already claimed
+    pub claimed: U128,
+}
+
+// Stake with gold which can be claimed now
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeView {
+    pub stake: Stake,
+    pub pending: U128,
+}
+
+impl Stake {
+    // Gold accrued since the hero was staked until `now` and not claimed yet
+    pub fn pending(&self, now: u64) -> Balance {
+        let elapsed = (now - self.staked_at.0) as u128;
+        self.daily_reward.0 * elapsed / NANOSECONDS_IN_DAY as u128 - self.claimed.0
+    }
+}
+
+// Gold accrued per day by staked hero of `rarity` with `stars`
+fn daily_reward(rarity: Rarity, stars: u64) -> Balance {
+    let base = match rarity {
+        Rarity::Common => 10,
+        Rarity::Rare => 25,
+        Rarity::Epic => 60,
+        Rarity::Ssr => 150,
+    };
+    base * (100 + STAKING_STAR_BONUS_PERCENT * stars) as u128 / 100
+}
+
+#[near_bindgen]
+impl Contract {
+    // Stake the caller's hero to accrue gold, the hero can't change hands, battle or
+    // be sent on expeditions until unstaked
+    #[payable]
+    pub fn stake_hero(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+        let owner_id = self.assert_token_owner(&token_id);
+        self.assert_not_locked(&token_id);
+        self.assert_not_rented(&token_id);
+        let rarity = self.rarity.get(&token_id).expect("token is not a hero");
+        let stars = self.stars.get(&token_id).unwrap_or(0);
+
+        self.internal_lock_hero(&token_id, env::current_account_id(), STAKING_LOCK_REASON.to_string(), U64(u64::MAX));
+        self.stakes.insert(&token_id, &Stake {
+            owner_id,
+            staked_at: U64(env::block_timestamp()),
+            daily_reward: U128(daily_reward(rarity, stars)),
+            claimed: U128(0),
+        });
+    }
+
+    // Claim gold accrued by the caller's staked hero, return claimed amount
+    pub fn claim_staking_rewards(&mut self, token_id: TokenId) -> U128 {
+        let mut stake = self.stakes.get(&token_id).expect("hero is not staked");
+        assert_eq!(env::predecessor_account_id(), stake.owner_id, "only staker can claim rewards");
+        let pending = stake.pending(env::block_timestamp());
+        stake.claimed = U128(stake.claimed.0 + pending);
+        self.stakes.insert(&token_id, &stake);
+        self.internal_add_gold(&stake.owner_id, pending);
+        U128(pending)
+    }
+
+    // Unlock the caller's staked hero and claim its rewards, return claimed amount
+    #[payable]
+    pub fn unstake_hero(&mut self, token_id: TokenId) -> U128 {
+        assert_one_yocto();
+        let stake = self.stakes.remove(&token_id).expect("hero is not staked");
+        assert_eq!(env::predecessor_account_id(), stake.owner_id, "only staker can unstake the hero");
+        self.locks.remove(&token_id);
+        let pending = stake.pending(env::block_timestamp());
+        self.internal_add_gold(&stake.owner_id, pending);
+        U128(pending)
+    }
+
+    // Get stake of the hero with gold which can be claimed now
+    pub fn get_stake(&self, token_id: TokenId) -> Option<StakeView> {
+        let stake = self.stakes.get(&token_id)?;
+        Some(StakeView { pending: U128(stake.pending(env::block_timestamp())), stake })
+    }
+
+    // Get gold balance of `account_id`
+    pub fn get_gold(&self, account_id: AccountId) -> U128 {
+        U128(self.gold.get(&account_id).unwrap_or(0))
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_add_gold(&mut self, account_id: &AccountId, amount: Balance) {
+        if amount > 0 {
+            self.gold.insert(account_id, &(self.get_gold(account_id.clone()).0 + amount));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stake(daily_reward: Balance) -> Stake {
+        Stake { owner_id: "owner.near".parse().unwrap(), staked_at: U64(1_000), daily_reward: U128(daily_reward), claimed: U128(0) }
+    }
+
+    #[test]
+    fn daily_reward_grows_with_rarity_and_stars() {
+        assert_eq!(daily_reward(Rarity::Common, 0), 10);
+        assert_eq!(daily_reward(Rarity::Rare, 0), 25);
+        assert_eq!(daily_reward(Rarity::Epic, 0), 60);
+        assert_eq!(daily_reward(Rarity::Ssr, 0), 150);
+        assert_eq!(daily_reward(Rarity::Common, 5), 20);
+        assert_eq!(daily_reward(Rarity::Ssr, 1), 180);
+    }
+
+    #[test]
+    fn pending_accrues_pro_rata() {
+        let stake = stake(100);
+        assert_eq!(stake.pending(1_000), 0);
+        assert_eq!(stake.pending(1_000 + NANOSECONDS_IN_DAY / 2), 50);
+        assert_eq!(stake.pending(1_000 + NANOSECONDS_IN_DAY), 100);
+        assert_eq!(stake.pending(1_000 + 3 * NANOSECONDS_IN_DAY), 300);
+    }
+
+    #[test]
+    fn pending_excludes_claimed_gold() {
+        let mut stake = stake(100);
+        let now = 1_000 + NANOSECONDS_IN_DAY * 3 / 10;
+        let claimed = stake.pending(now);
+        assert_eq!(claimed, 30);
+        stake.claimed = U128(claimed);
+        assert_eq!(stake.pending(now), 0);
+        // Rounded down gold is not lost on later claims
+        assert_eq!(stake.pending(1_000 + NANOSECONDS_IN_DAY), 70);
+    }
+}
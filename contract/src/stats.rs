@@ -0,0 +1,46 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use std::collections::HashMap;
+
+// Number of crafted, burned and existing heroes
+#[derive(Serialize, Deserialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SupplyStats {
+    pub minted: u64,
+    pub burned: u64,
+    pub alive: u64,
+}
+
+// Supply of heroes by rarity and by template with total revenue of crafting
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CollectionStats {
+    pub rarities: HashMap<Rarity, SupplyStats>,
+    pub templates: HashMap<String, SupplyStats>,
+    pub total_revenue: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Get supply of heroes and total revenue of crafting
+    pub fn get_collection_stats(&self) -> CollectionStats {
+        let mut rarities: HashMap<Rarity, SupplyStats> = HashMap::new();
+        let mut templates = HashMap::new();
+        for hero in HEROES.iter() {
+            let minted = self.minted.get(&hero.media_url.to_string()).unwrap_or(0);
+            let burned = self.burned.get(&hero.media_url.to_string()).unwrap_or(0);
+
+            let rarity_stats = rarities.entry(hero.rarity).or_default();
+            rarity_stats.minted += minted;
+            rarity_stats.burned += burned;
+            rarity_stats.alive += minted - burned;
+            templates.insert(hero.media_url.to_string(), SupplyStats { minted, burned, alive: minted - burned });
+        }
+
+        CollectionStats {
+            rarities,
+            templates,
+            total_revenue: U128(self.total_revenue),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+use crate::heroes::roll_boosted_rarity;
+
+// Summoning settings, parents either rest for `cooldown` or are burned
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SummonConfig {
+    pub fee: U128,
+    // Nanoseconds before parents can summon again
+    pub cooldown: U64,
+    pub burn_parents: bool,
+}
+
+// Raise of the odds of a rare child in percents, rarer parents and parents of the same
+// faction raise it up to the cap
+fn summon_bonus_percent(parent_a: &HeroData, parent_b: &HeroData) -> u32 {
+    let rarity_bonus = SUMMON_RARITY_BONUS_PERCENT * (parent_a.rarity as u32 + parent_b.rarity as u32);
+    let faction_bonus = if parent_a.faction == parent_b.faction { SUMMON_FACTION_BONUS_PERCENT } else { 0 };
+    (rarity_bonus + faction_bonus).min(MAX_SUMMON_BONUS_PERCENT)
+}
+
+#[near_bindgen]
+impl Contract {
+    // Summon a new hero from two unlocked heroes owned by the caller attaching the fee.
+    // Rarer parents raise the odds of a rare child, parents of the same faction raise
+    // them further, and heroes of parent factions are more likely to be summoned
+    #[payable]
+    pub fn summon(&mut self, parent_a: TokenId, parent_b: TokenId) -> CraftedHero {
+        assert_ne!(parent_a, parent_b, "parents must be different heroes");
+        let config = self.get_summon_config();
+        assert!(env::attached_deposit() >= config.fee.0, "not enough currency to summon a hero");
+        let owner_id = env::predecessor_account_id();
+        let [parent_a_data, parent_b_data] = [&parent_a, &parent_b].map(|token_id| {
+            self.assert_token_owner(token_id);
+            self.assert_not_locked(token_id);
+            self.assert_not_rented(token_id);
+            let remaining = self.get_summon_cooldown(token_id.clone()).0;
+            assert_eq!(remaining, 0, "{} can summon again in {} nanoseconds", token_id, remaining);
+            self.template.get(token_id).and_then(|media_url| hero_data(&media_url)).expect("token is not a hero")
+        });
+        self.total_revenue += env::attached_deposit();
+
+        let rand = random_bytes(0);
+        let rarity = roll_boosted_rarity(u16::from_be_bytes([rand[0], rand[5]]), summon_bonus_percent(parent_a_data, parent_b_data));
+        let hero_data = self.internal_roll_hero(rarity, u16::from_be_bytes([rand[1], rand[4]]), |hero| {
+            if hero.faction == parent_a_data.faction || hero.faction == parent_b_data.faction {
+                hero.weight * SUMMON_FACTION_RATE_UP
+            } else {
+                hero.weight
+            }
+        });
+        let child = self.internal_mint_hero(hero_token_id(hero_data, &rand, 0), &owner_id, hero_data);
+        // The child rests like its parents before it can summon
+        self.summon_cooldowns.insert(&child.token_id, &(env::block_timestamp() + config.cooldown.0));
+
+        for token_id in [&parent_a, &parent_b] {
+            let mut children = self.children.get(token_id).unwrap_or_default();
+            children.push(child.token_id.clone());
+            self.children.insert(token_id, &children);
+            if config.burn_parents {
+                self.internal_burn_hero(&owner_id, token_id);
+            } else {
+                self.summon_cooldowns.insert(token_id, &(env::block_timestamp() + config.cooldown.0));
+            }
+        }
+        self.parents.insert(&child.token_id, &(parent_a, parent_b));
+        child
+    }
+
+    // Get nanoseconds until the hero can summon again, 0 if it can summon now
+    pub fn get_summon_cooldown(&self, token_id: TokenId) -> U64 {
+        let available_at = self.summon_cooldowns.get(&token_id).unwrap_or(0);
+        U64(available_at.saturating_sub(env::block_timestamp()))
+    }
+
+    // Get parents of a summoned hero
+    pub fn get_parents(&self, token_id: TokenId) -> Option<(TokenId, TokenId)> {
+        self.parents.get(&token_id)
+    }
+
+    // Get heroes summoned by the hero, oldest first
+    pub fn get_children(&self, token_id: TokenId) -> Vec<TokenId> {
+        self.children.get(&token_id).unwrap_or_default()
+    }
+
+    // Get summoning fee, parent cooldown and whether parents are burned
+    pub fn get_summon_config(&self) -> SummonConfig {
+        self.summon_config.get().unwrap()
+    }
+
+    // Set summoning fee, parent cooldown and whether parents are burned
+    pub fn set_summon_config(&mut self, summon_config: SummonConfig) {
+        self.assert_owner();
+        self.summon_config.set(&summon_config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heroes::boosted_rarity_weights;
+
+    fn template(rarity: Rarity, faction: Faction) -> &'static HeroData {
+        HEROES.iter().find(|hero| hero.rarity == rarity && hero.faction == faction).unwrap()
+    }
+
+    #[test]
+    fn summon_bonus_grows_with_parents_up_to_cap() {
+        let common = template(Rarity::Common, Faction::Human);
+        let other_common = template(Rarity::Common, Faction::Demon);
+        assert_eq!(summon_bonus_percent(common, other_common), 0);
+        assert_eq!(summon_bonus_percent(common, common), SUMMON_FACTION_BONUS_PERCENT);
+        let ssr = template(Rarity::Ssr, Faction::Human);
+        assert_eq!(summon_bonus_percent(ssr, ssr), MAX_SUMMON_BONUS_PERCENT);
+    }
+
+    #[test]
+    fn summon_odds_keep_rarer_heroes_rarer() {
+        for parent_a in HEROES.iter() {
+            for parent_b in HEROES.iter() {
+                let bonus_percent = summon_bonus_percent(parent_a, parent_b);
+                let [common, rare, epic, ssr] = boosted_rarity_weights(bonus_percent);
+                assert_eq!(common + rare + epic + ssr, 65_536);
+                assert!(ssr < epic && epic < rare && rare < common, "{} and {}", parent_a.name, parent_b.name);
+                // Odds of each rarity above Common grow by the bonus at most up to the cap
+                for (weight, craft_weight) in [(rare, 26 * 256), (epic, 13 * 256), (ssr, 2 * 256)] {
+                    assert!(weight >= craft_weight);
+                    assert!(weight * 100 <= craft_weight * (100 + MAX_SUMMON_BONUS_PERCENT));
+                }
+            }
+        }
+    }
+}
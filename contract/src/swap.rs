@@ -0,0 +1,148 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+// Lock reason of heroes offered in the swap, ties the lock to the offer
+fn swap_lock_reason(swap_id: u64) -> String {
+    format!("swap {}", swap_id)
+}
+
+// Offer to swap maker heroes and optional NEAR for heroes of the taker. Offered
+// heroes are locked until the offer expires
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapOffer {
+    pub maker_id: AccountId,
+    pub taker_id: AccountId,
+    pub offered: Vec<TokenId>,
+    pub requested: Vec<TokenId>,
+    // NEAR paid to the taker
+    pub deposit: U128,
+    // Block timestamp in nanoseconds
+    pub expires_at: U64,
+    // Storage deposit refunded to the maker
+    pub storage_deposit: U128,
+}
+
+// Swap offer with its id
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapOfferView {
+    pub swap_id: u64,
+    pub offer: SwapOffer,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Offer the caller's heroes and attached deposit to `taker_id` for `requested`
+    // heroes until `expires_at`, return offer id. Part of the deposit covers storage
+    // until the offer is removed
+    #[payable]
+    pub fn create_swap(&mut self, taker_id: AccountId, offered: Vec<TokenId>, requested: Vec<TokenId>, expires_at: U64) -> u64 {
+        let maker_id = env::predecessor_account_id();
+        assert!(env::attached_deposit() >= SWAP_DEPOSIT, "attach at least {} yoctoNEAR to create the offer", SWAP_DEPOSIT);
+        let deposit = env::attached_deposit() - SWAP_DEPOSIT;
+        assert_ne!(maker_id, taker_id, "can't swap with yourself");
+        assert!(!offered.is_empty() || deposit > 0, "offer must contain heroes or NEAR");
+        assert!(!requested.is_empty(), "offer must request heroes");
+        assert!(expires_at.0 > env::block_timestamp(), "offer must expire in the future");
+        for token_id in requested.iter() {
+            assert_eq!(self.tokens.owner_by_id.get(token_id), Some(taker_id.clone()), "{} is not owned by {}", token_id, taker_id);
+        }
+
+        let swap_id = self.next_swap_id;
+        self.next_swap_id += 1;
+        for token_id in offered.iter() {
+            self.assert_token_owner(token_id);
+            self.internal_before_transfer(token_id);
+            self.internal_lock_hero(token_id, env::current_account_id(), swap_lock_reason(swap_id), expires_at);
+        }
+        for account_id in [&maker_id, &taker_id] {
+            index_account_id(&mut self.swaps_by_account, account_id, swap_id, |account_id_hash| {
+                StorageKey::SwapsByAccountInner { account_id_hash }
+            });
+        }
+        self.swaps.insert(&swap_id, &SwapOffer {
+            maker_id,
+            taker_id,
+            offered,
+            requested,
+            deposit: U128(deposit),
+            expires_at,
+            storage_deposit: U128(SWAP_DEPOSIT),
+        });
+        swap_id
+    }
+
+    // Exchange heroes of the offer, only its taker can call it before the offer expires
+    #[payable]
+    pub fn accept_swap(&mut self, swap_id: u64) {
+        assert_one_yocto();
+        let offer = self.internal_remove_swap(swap_id);
+        assert_eq!(env::predecessor_account_id(), offer.taker_id, "only taker can accept the offer");
+        assert!(env::block_timestamp() < offer.expires_at.0, "offer has expired");
+
+        for token_id in offer.offered.iter() {
+            self.internal_unlock_swapped_hero(token_id, swap_id);
+            self.internal_move_hero(token_id, &offer.maker_id, &offer.taker_id);
+        }
+        for token_id in offer.requested.iter() {
+            self.internal_move_hero(token_id, &offer.taker_id, &offer.maker_id);
+        }
+        if offer.deposit.0 > 0 {
+            Promise::new(offer.taker_id).transfer(offer.deposit.0);
+        }
+        Promise::new(offer.maker_id).transfer(offer.storage_deposit.0);
+    }
+
+    // Withdraw the offer, unlock its heroes and refund its deposits to the maker. Its
+    // maker or taker can call it, anyone once the offer has expired
+    #[payable]
+    pub fn cancel_swap(&mut self, swap_id: u64) {
+        assert_one_yocto();
+        let offer = self.internal_remove_swap(swap_id);
+        let account_id = env::predecessor_account_id();
+        assert!(
+            account_id == offer.maker_id || account_id == offer.taker_id || env::block_timestamp() >= offer.expires_at.0,
+            "only maker or taker can cancel the offer before it expires"
+        );
+
+        for token_id in offer.offered.iter() {
+            self.internal_unlock_swapped_hero(token_id, swap_id);
+        }
+        Promise::new(offer.maker_id).transfer(offer.deposit.0 + offer.storage_deposit.0);
+    }
+
+    // Get swap offer
+    pub fn get_swap(&self, swap_id: u64) -> Option<SwapOffer> {
+        self.swaps.get(&swap_id)
+    }
+
+    // Get offers made or received by `account_id`, including expired offers which
+    // their makers can still cancel
+    pub fn get_swaps_for_account(&self, account_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<SwapOfferView> {
+        account_ids_page(&self.swaps_by_account, &account_id, from_index, limit)
+            .into_iter()
+            .filter_map(|swap_id| Some(SwapOfferView { swap_id, offer: self.swaps.get(&swap_id)? }))
+            .collect()
+    }
+}
+
+impl Contract {
+    // Remove offer from the offers and the index of its accounts
+    fn internal_remove_swap(&mut self, swap_id: u64) -> SwapOffer {
+        let offer = self.swaps.remove(&swap_id).expect("offer not found");
+        unindex_account_id(&mut self.swaps_by_account, &offer.maker_id, swap_id);
+        unindex_account_id(&mut self.swaps_by_account, &offer.taker_id, swap_id);
+        offer
+    }
+
+    // Remove lock of the hero set by the offer, keeping locks set by later offers or by
+    // game operators after the offer expired
+    fn internal_unlock_swapped_hero(&mut self, token_id: &TokenId, swap_id: u64) {
+        if let Some(lock) = self.locks.get(token_id) {
+            if lock.locked_by == env::current_account_id() && lock.reason == swap_lock_reason(swap_id) {
+                self.locks.remove(token_id);
+            }
+        }
+    }
+}
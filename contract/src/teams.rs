@@ -0,0 +1,95 @@
+use crate::*;
+use std::collections::HashSet;
+
+// Saved team with stats of its heroes
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TeamView {
+    pub name: String,
+    pub heroes: Vec<HeroView>,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Save team of up to 5 unlocked heroes owned by the caller under `name`,
+    // replacing the team with the same name
+    pub fn save_team(&mut self, name: String, heroes: Vec<TokenId>) {
+        let account_id = env::predecessor_account_id();
+        assert!(!name.is_empty(), "team name can't be empty");
+        assert!(name.len() <= MAX_TEAM_NAME_LENGTH, "team name can't be longer than {} bytes", MAX_TEAM_NAME_LENGTH);
+        assert!(!heroes.is_empty(), "team can't be empty");
+        assert!(heroes.len() <= MAX_TEAM_SIZE as usize, "team can't have more than {} heroes", MAX_TEAM_SIZE);
+        assert_eq!(heroes.iter().collect::<HashSet<_>>().len(), heroes.len(), "team can't have duplicate heroes");
+        for token_id in heroes.iter() {
+            self.assert_token_owner(token_id);
+            self.assert_not_locked(token_id);
+        }
+
+        let mut teams = self.teams.get(&account_id).unwrap_or_default();
+        assert!(
+            teams.contains_key(&name) || teams.len() < MAX_TEAMS as usize,
+            "can't have more than {} teams", MAX_TEAMS
+        );
+        teams.insert(name, heroes);
+        self.teams.insert(&account_id, &teams);
+    }
+
+    // Delete the caller's team
+    pub fn delete_team(&mut self, name: String) {
+        let account_id = env::predecessor_account_id();
+        let mut teams = self.teams.get(&account_id).unwrap_or_default();
+        assert!(teams.remove(&name).is_some(), "team not found");
+        if teams.is_empty() {
+            self.teams.remove(&account_id);
+        } else {
+            self.teams.insert(&account_id, &teams);
+        }
+    }
+
+    // Get team of `account_id` with stats of its heroes
+    pub fn get_team(&self, account_id: AccountId, name: String) -> Option<TeamView> {
+        let heroes = self.teams.get(&account_id)?.remove(&name)?;
+        Some(TeamView { name, heroes: self.internal_team_heroes(&account_id, &heroes) })
+    }
+
+    // Get all teams of `account_id` with stats of their heroes, ordered by name
+    pub fn get_teams(&self, account_id: AccountId) -> Vec<TeamView> {
+        let mut teams: Vec<TeamView> = self.teams
+            .get(&account_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, heroes)| TeamView { name, heroes: self.internal_team_heroes(&account_id, &heroes) })
+            .collect();
+        teams.sort_by(|a, b| a.name.cmp(&b.name));
+        teams
+    }
+}
+
+impl Contract {
+    // Views of team heroes still owned by `account_id`, skipping heroes being
+    // transferred with `nft_transfer_call`
+    fn internal_team_heroes(&self, account_id: &AccountId, heroes: &[TokenId]) -> Vec<HeroView> {
+        heroes
+            .iter()
+            .filter_map(|token_id| self.get_hero(token_id.clone()))
+            .filter(|hero| &hero.token.owner_id == account_id)
+            .collect()
+    }
+
+    // Remove the hero which left `account_id` from its teams, dropping emptied teams
+    pub(crate) fn internal_remove_from_teams(&mut self, account_id: &AccountId, token_id: &TokenId) {
+        let mut teams = match self.teams.get(account_id) {
+            Some(teams) => teams,
+            None => return,
+        };
+        for heroes in teams.values_mut() {
+            heroes.retain(|hero_id| hero_id != token_id);
+        }
+        teams.retain(|_, heroes| !heroes.is_empty());
+        if teams.is_empty() {
+            self.teams.remove(account_id);
+        } else {
+            self.teams.insert(account_id, &teams);
+        }
+    }
+}
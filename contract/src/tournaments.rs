@@ -0,0 +1,256 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+// Team registered for a tournament
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TournamentEntry {
+    pub account_id: AccountId,
+    pub team: Vec<TokenId>,
+}
+
+// Tournament with paid registration, its prize pool is split by rank once the game
+// server submits results
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Tournament {
+    pub name: String,
+    // Block timestamps in nanoseconds
+    pub registration_starts_at: U64,
+    pub registration_ends_at: U64,
+    pub entry_fee: U128,
+    pub max_entries: u32,
+    // Studio cut of the prize pool and share of the rest for each rank in basis points
+    pub studio_fee: u32,
+    pub prizes: Vec<u32>,
+    #[serde(default)]
+    pub entries: Vec<TournamentEntry>,
+    // Accounts ordered by rank, set once results are submitted
+    #[serde(default)]
+    pub ranking: Option<Vec<AccountId>>,
+}
+
+// Tournament with its id and collected entry fees
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TournamentView {
+    pub tournament_id: u64,
+    pub tournament: Tournament,
+    pub prize_pool: U128,
+}
+
+impl Tournament {
+    pub fn prize_pool(&self) -> Balance {
+        self.entry_fee.0 * self.entries.len() as u128
+    }
+
+    pub fn is_registration_open(&self) -> bool {
+        let now = env::block_timestamp();
+        self.registration_starts_at.0 <= now && now < self.registration_ends_at.0
+    }
+
+    // Split the prize pool into prizes of the first `ranked` ranks and the studio payout,
+    // which is its cut plus prizes of missing ranks and rounding
+    pub fn split_prize_pool(&self, ranked: usize) -> (Vec<Balance>, Balance) {
+        let prize_pool = self.prize_pool();
+        let studio_fee = prize_pool * self.studio_fee as u128 / 10_000;
+        let prizes: Vec<Balance> = self.prizes
+            .iter()
+            .take(ranked)
+            .map(|share| (prize_pool - studio_fee) * *share as u128 / 10_000)
+            .collect();
+        let studio_payout = prize_pool - prizes.iter().sum::<Balance>();
+        (prizes, studio_payout)
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Add new tournament and return its id
+    pub fn add_tournament(&mut self, tournament: Tournament) -> u64 {
+        self.assert_owner();
+        assert!(
+            tournament.registration_starts_at.0 < tournament.registration_ends_at.0,
+            "registration must start before it ends"
+        );
+        assert!(tournament.max_entries > 0, "tournament must allow entries");
+        assert!(
+            tournament.max_entries <= MAX_TOURNAMENT_ENTRIES,
+            "tournament can't have more than {} entries", MAX_TOURNAMENT_ENTRIES
+        );
+        assert!(!tournament.prizes.is_empty(), "tournament must have prizes");
+        assert!(
+            tournament.prizes.len() <= MAX_TOURNAMENT_PRIZES,
+            "tournament can't have more than {} prizes", MAX_TOURNAMENT_PRIZES
+        );
+        assert!(tournament.studio_fee <= 10_000, "studio fee can't exceed 10000");
+        assert!(tournament.prizes.iter().sum::<u32>() <= 10_000, "prizes can't exceed 10000");
+        assert!(tournament.entries.is_empty() && tournament.ranking.is_none(), "tournament must be new");
+
+        let tournament_id = self.next_tournament_id;
+        self.next_tournament_id += 1;
+        self.tournaments.insert(&tournament_id, &tournament);
+        tournament_id
+    }
+
+    // Cancel tournament without results and refund entry fees
+    pub fn cancel_tournament(&mut self, tournament_id: u64) {
+        self.assert_owner();
+        let tournament = self.tournaments.get(&tournament_id).expect("tournament not found");
+        assert!(tournament.ranking.is_none(), "tournament is already finished");
+        self.tournaments.remove(&tournament_id);
+        if tournament.entry_fee.0 > 0 {
+            for entry in tournament.entries {
+                Promise::new(entry.account_id).transfer(tournament.entry_fee.0);
+            }
+        }
+    }
+
+    // Register `team` of heroes used by the caller attaching the entry fee
+    #[payable]
+    pub fn register_tournament(&mut self, tournament_id: u64, team: Vec<TokenId>) {
+        let account_id = env::predecessor_account_id();
+        let mut tournament = self.tournaments.get(&tournament_id).expect("tournament not found");
+        assert!(tournament.is_registration_open(), "registration is closed");
+        assert!(tournament.entries.len() < tournament.max_entries as usize, "tournament is full");
+        assert!(
+            tournament.entries.iter().all(|entry| entry.account_id != account_id),
+            "{} is already registered", account_id
+        );
+        self.internal_fighters(&account_id, &team);
+        let deposit = env::attached_deposit();
+        assert!(deposit >= tournament.entry_fee.0, "not enough currency to pay the entry fee");
+
+        if deposit > tournament.entry_fee.0 {
+            Promise::new(account_id.clone()).transfer(deposit - tournament.entry_fee.0);
+        }
+        tournament.entries.push(TournamentEntry { account_id, team });
+        self.tournaments.insert(&tournament_id, &tournament);
+    }
+
+    // Withdraw the caller's registration while it is open and refund the entry fee
+    pub fn leave_tournament(&mut self, tournament_id: u64) {
+        let account_id = env::predecessor_account_id();
+        let mut tournament = self.tournaments.get(&tournament_id).expect("tournament not found");
+        assert!(tournament.is_registration_open(), "registration is closed");
+        let index = tournament.entries
+            .iter()
+            .position(|entry| entry.account_id == account_id)
+            .expect("not registered");
+        tournament.entries.remove(index);
+        self.tournaments.insert(&tournament_id, &tournament);
+        if tournament.entry_fee.0 > 0 {
+            Promise::new(account_id).transfer(tournament.entry_fee.0);
+        }
+    }
+
+    // Submit accounts ordered by rank, only game servers can call it after registration
+    // ends. The studio takes its cut, ranked accounts get their prizes and prizes of
+    // missing ranks go to the studio
+    pub fn submit_tournament_results(&mut self, tournament_id: u64, ranking: Vec<AccountId>) {
+        self.assert_game_server();
+        let mut tournament = self.tournaments.get(&tournament_id).expect("tournament not found");
+        assert!(env::block_timestamp() >= tournament.registration_ends_at.0, "registration is still open");
+        assert!(tournament.ranking.is_none(), "results are already submitted");
+        assert!(ranking.len() <= tournament.prizes.len(), "only {} ranks get prizes", tournament.prizes.len());
+        for (index, account_id) in ranking.iter().enumerate() {
+            assert!(
+                tournament.entries.iter().any(|entry| &entry.account_id == account_id),
+                "{} is not registered", account_id
+            );
+            assert!(!ranking[..index].contains(account_id), "{} is ranked twice", account_id);
+        }
+
+        let prize_pool = tournament.prize_pool();
+        let (prizes, studio_payout) = tournament.split_prize_pool(ranking.len());
+        for (account_id, prize) in ranking.iter().zip(prizes) {
+            if prize > 0 {
+                Promise::new(account_id.clone()).transfer(prize);
+            }
+        }
+        if studio_payout > 0 {
+            Promise::new(self.owner_id.clone()).transfer(studio_payout);
+        }
+
+        log!("EVENT_JSON:{}", json!({
+            "standard": EVENT_STANDARD,
+            "version": EVENT_VERSION,
+            "event": "tournament_results",
+            "data": [{
+                "tournament_id": tournament_id,
+                "ranking": &ranking,
+                "prize_pool": U128(prize_pool),
+            }],
+        }));
+        tournament.ranking = Some(ranking);
+        self.tournaments.insert(&tournament_id, &tournament);
+    }
+
+    // Get tournament with its prize pool
+    pub fn get_tournament(&self, tournament_id: u64) -> Option<TournamentView> {
+        let tournament = self.tournaments.get(&tournament_id)?;
+        Some(TournamentView { tournament_id, prize_pool: U128(tournament.prize_pool()), tournament })
+    }
+
+    // Get tournaments with their prize pools, oldest first
+    pub fn get_tournaments(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<TournamentView> {
+        let limit = limit.unwrap_or(u64::MAX);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        self.tournaments
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .map(|(tournament_id, tournament)| TournamentView {
+                tournament_id,
+                prize_pool: U128(tournament.prize_pool()),
+                tournament,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tournament(entries: usize, studio_fee: u32, prizes: Vec<u32>) -> Tournament {
+        Tournament {
+            name: "Cup".to_string(),
+            registration_starts_at: U64(0),
+            registration_ends_at: U64(1),
+            entry_fee: U128(1_000),
+            max_entries: MAX_TOURNAMENT_ENTRIES,
+            studio_fee,
+            prizes,
+            entries: (0..entries)
+                .map(|index| TournamentEntry { account_id: format!("player{}.near", index).parse().unwrap(), team: vec![] })
+                .collect(),
+            ranking: None,
+        }
+    }
+
+    #[test]
+    fn prizes_are_shares_of_pool_after_studio_fee() {
+        let tournament = tournament(10, 1_000, vec![5_000, 3_000, 2_000]);
+        assert_eq!(tournament.prize_pool(), 10_000);
+        assert_eq!(tournament.split_prize_pool(3), (vec![4_500, 2_700, 1_800], 1_000));
+    }
+
+    #[test]
+    fn prizes_of_missing_ranks_go_to_studio() {
+        let tournament = tournament(10, 1_000, vec![5_000, 3_000, 2_000]);
+        assert_eq!(tournament.split_prize_pool(1), (vec![4_500], 5_500));
+        assert_eq!(tournament.split_prize_pool(0), (vec![], 10_000));
+    }
+
+    #[test]
+    fn split_pays_out_whole_pool() {
+        for entries in [0, 1, 3, 7, MAX_TOURNAMENT_ENTRIES as usize] {
+            let tournament = tournament(entries, 333, vec![3_333, 3_333, 3_333]);
+            for ranked in 0..=3 {
+                let (prizes, studio_payout) = tournament.split_prize_pool(ranked);
+                assert_eq!(prizes.iter().sum::<Balance>() + studio_payout, tournament.prize_pool());
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::*;
+
+// What happens to hero stats when the hero changes hands
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum TransferPolicy {
+    KeepStats,
+    ResetExperience,
+    // Remove given percent of stars rounding up
+    DecayStars { percent: u8 },
+    // Forbid transfers for given hours after a stats update
+    LockAfterUpdate { hours: u64 },
+}
+
+#[near_bindgen]
+impl Contract {
+    // Get policy applied to hero stats on transfer
+    pub fn get_transfer_policy(&self) -> TransferPolicy {
+        self.transfer_policy
+    }
+
+    // Set policy applied to hero stats on transfer
+    pub fn set_transfer_policy(&mut self, transfer_policy: TransferPolicy) {
+        self.assert_owner();
+        if let TransferPolicy::DecayStars { percent } = transfer_policy {
+            assert!(percent <= 100, "percent can't be greater than 100");
+        }
+        self.transfer_policy = transfer_policy;
+    }
+}
+
+// NFT core methods with transfer policy applied
+#[near_bindgen]
+impl NonFungibleTokenCore for Contract {
+    #[payable]
+    fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        self.internal_before_transfer(&token_id);
+        let owner_id = self.tokens.owner_by_id.get(&token_id).expect("Token not found");
+        self.tokens.nft_transfer(receiver_id, token_id.clone(), approval_id, memo);
+        self.internal_after_transfer(&token_id, &owner_id);
+    }
+
+    #[payable]
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        // Stats are updated in `nft_resolve_transfer` once the receiver keeps the hero
+        self.internal_before_transfer(&token_id);
+        self.tokens.nft_transfer_call(receiver_id, token_id, approval_id, memo, msg)
+    }
+
+    fn nft_token(&self, token_id: TokenId) -> Option<Token> {
+        self.tokens.nft_token(token_id)
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenResolver for Contract {
+    #[private]
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool {
+        let transferred = self.tokens.nft_resolve_transfer(
+            previous_owner_id.clone(),
+            receiver_id,
+            token_id.clone(),
+            approved_account_ids,
+        );
+        if transferred {
+            self.internal_after_transfer(&token_id, &previous_owner_id);
+        }
+        transferred
+    }
+}
+
+impl Contract {
+    // Panic if the hero can't change hands now
+    pub(crate) fn internal_before_transfer(&self, token_id: &TokenId) {
+        self.assert_not_locked(token_id);
+        assert!(!self.soulbound.contains(token_id), "soulbound hero can't be transferred");
+        self.assert_not_rented(token_id);
+        if let TransferPolicy::LockAfterUpdate { hours } = self.transfer_policy {
+            if let Some(updated_at) = self.stats_updated_at.get(token_id) {
+                let unlocks_at = updated_at + hours * NANOSECONDS_IN_HOUR;
+                assert!(env::block_timestamp() >= unlocks_at, "hero can't be transferred until {}", unlocks_at);
+            }
+        }
+    }
+
+    // Transfer hero from `owner_id` to `receiver_id` on behalf of the contract
+    pub(crate) fn internal_move_hero(&mut self, token_id: &TokenId, owner_id: &AccountId, receiver_id: &AccountId) {
+        self.internal_before_transfer(token_id);
+        self.tokens.internal_transfer(owner_id, receiver_id, token_id, None, None);
+        self.internal_after_transfer(token_id, owner_id);
+    }
+
+    // Transfer hero which was escrowed by a contract lock from `owner_id` to `receiver_id`
+    // without checking the transfer policy again
+    pub(crate) fn internal_move_escrowed_hero(&mut self, token_id: &TokenId, owner_id: &AccountId, receiver_id: &AccountId) {
+        self.tokens.internal_transfer(owner_id, receiver_id, token_id, None, None);
+        self.internal_after_transfer(token_id, owner_id);
+    }
+
+    // Close sale and rental offer of the hero which left `previous_owner_id`, remove
+    // it from their teams, apply transfer policy and update leaderboards
+    pub(crate) fn internal_after_transfer(&mut self, token_id: &TokenId, previous_owner_id: &AccountId) {
+        self.sales.remove(token_id);
+        self.rental_offers.remove(token_id);
+        self.internal_remove_from_teams(previous_owner_id, token_id);
+        match self.transfer_policy {
+            TransferPolicy::KeepStats | TransferPolicy::LockAfterUpdate { .. } => {},
+            TransferPolicy::ResetExperience => {
+                self.experience.insert(token_id, &0);
+            },
+            TransferPolicy::DecayStars { percent } => {
+                let stars = self.stars.get(token_id).unwrap_or(0);
+                let lost = (stars * percent as u64).div_ceil(100);
+                self.stars.insert(token_id, &(stars - lost));
+            },
+        }
+        self.internal_rank_hero(token_id);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::*;
+use near_sdk::json_types::U128;
+
+use crate::heroes::{combat_stats, hero_level};
+
+// Token joined with hero stats
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HeroView {
+    pub token: Token,
+    pub media_url: String,
+    pub edition: u64,
+    pub rarity: Rarity,
+    pub faction: Faction,
+    pub class: HeroClass,
+    pub stars: u64,
+    pub experience: u64,
+    pub level: u64,
+    pub maximum_level: u64,
+    pub power: u64,
+    pub health: u64,
+    pub soulbound: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Get token of a hero with its stats
+    pub fn get_hero(&self, token_id: TokenId) -> Option<HeroView> {
+        let token = self.nft_token(token_id)?;
+        self.internal_hero_view(token)
+    }
+
+    // Get heroes of `account_id` with their stats. Pagination is applied to the heroes,
+    // skipping tokens which aren't heroes, in the order of `nft_tokens_for_owner`
+    pub fn get_heroes_for_owner(&self, account_id: AccountId, from_index: Option<U128>, limit: Option<u64>) -> Vec<HeroView> {
+        let limit = limit.unwrap_or(u64::MAX);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        let token_ids = match self.tokens.tokens_per_owner.as_ref().and_then(|tokens_per_owner| tokens_per_owner.get(&account_id)) {
+            Some(token_ids) => token_ids,
+            None => return vec![],
+        };
+        token_ids
+            .iter()
+            .filter_map(|token_id| self.get_hero(token_id))
+            .skip(from_index.map_or(0, |from_index| from_index.0 as usize))
+            .take(limit as usize)
+            .collect()
+    }
+}
+
+impl Contract {
+    // Hero view of the token, `None` for tokens not minted from a hero template
+    pub(crate) fn internal_hero_view(&self, token: Token) -> Option<HeroView> {
+        let token_id = &token.token_id;
+        let media_url = self.template.get(token_id)?;
+        let hero_data = hero_data(&media_url)?;
+        let edition = self.edition.get(token_id)?;
+        let stars = self.stars.get(token_id).unwrap_or(0);
+        let experience = self.experience.get(token_id).unwrap_or(0);
+        let maximum_level = self.maximum_level.get(token_id).unwrap_or(0);
+        let level = hero_level(experience, maximum_level);
+        let (power, health) = combat_stats(hero_data, stars, level);
+
+        Some(HeroView {
+            edition,
+            rarity: hero_data.rarity,
+            faction: hero_data.faction,
+            class: hero_data.class,
+            media_url,
+            stars,
+            experience,
+            level,
+            maximum_level,
+            power,
+            health,
+            soulbound: self.soulbound.contains(token_id),
+            token,
+        })
+    }
+}
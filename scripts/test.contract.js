@@ -32,6 +32,16 @@ const main = async () => {
   console.log("Try to get hero stats ...");
   sh.exec(`near view ${contractName} get_stats '{"token_id": "${tokenId}"}'`);
 
+  // Allow contract account to act as game server and approve it as a game operator of the hero
+  console.log("Try to approve game operator ...");
+  sh.exec(
+    `near call ${contractName} add_game_server '{"account_id": "${contractName}"}' --account-id ${contractName}`
+  );
+  sh.exec(
+    `near call ${contractName} approve_operator '{"token_id": "${tokenId}", "operator_id": "${contractName}"}' --account-id sergantche.testnet --deposit-yocto 1`
+  );
+  sh.exec(`near view ${contractName} get_operators '{"token_id": "${tokenId}"}'`);
+
   // Update hero statistics and get data again
   console.log("Try to update hero stats ...");
   sh.exec(